@@ -0,0 +1,93 @@
+use std::{cell::RefCell, fmt::Debug, rc::Rc};
+
+use crate::{lexer::tokens::Token, parser::ast::{Param, Stmt, VarPattern}, util::Value};
+
+use super::{entity::Instance, environment::{Environment, VarAttrib}, Interpreter, Unwind};
+
+pub trait Callable: Debug {
+    fn arity(&self) -> usize;
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Value>) -> Value;
+    fn name(&self) -> String;
+}
+
+pub struct RogueFunction {
+    name: Token,
+    return_type: Option<Token>,
+    params: Vec<VarPattern>,
+    body: Rc<Vec<Box<dyn Stmt>>>,
+    // the scope the function was declared in
+    closure: Rc<RefCell<Environment>>,
+    is_initializer: bool,
+}
+
+impl RogueFunction {
+    pub fn new(name: Token, return_type: Option<Token>, params: &[Param], body: Rc<Vec<Box<dyn Stmt>>>, closure: Rc<RefCell<Environment>>, is_initializer: bool) -> Self {
+        let params: Vec<VarPattern> = params.iter().map(Param::pattern).collect();
+        Self { name, return_type, params, body, closure, is_initializer }
+    }
+
+    /// Creates a copy of this method whose closure has `this` bound to `instance`.
+    pub fn bind(&self, instance: &Rc<RefCell<Instance>>) -> RogueFunction {
+        let mut environment: Environment = Environment::with_enclosing(Rc::clone(&self.closure));
+        environment.define("this".to_string(), VarAttrib(None, Some(Value::Instance(Rc::clone(instance))), false));
+
+        RogueFunction {
+            name: self.name.clone(),
+            return_type: self.return_type.clone(),
+            params: self.params.clone(),
+            body: Rc::clone(&self.body),
+            closure: Rc::new(RefCell::new(environment)),
+            is_initializer: self.is_initializer,
+        }
+    }
+}
+
+impl Debug for RogueFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<fn {}>", self.name.lexeme)
+    }
+}
+
+impl Callable for RogueFunction {
+    fn arity(&self) -> usize {
+        self.params.len()
+    }
+
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Value>) -> Value {
+        let mut environment: Environment = Environment::with_enclosing(Rc::clone(&self.closure));
+
+        for (param, argument) in self.params.iter().zip(arguments) {
+            if !interpreter.bind_pattern(&mut environment, param, argument) {
+                return Value::Null;
+            }
+        }
+
+        interpreter.execute_block(&self.body, environment);
+
+        let mut value: Value = match interpreter.unwind.take() {
+            Some(Unwind::Return(value)) => value,
+            // errors keep unwinding past the call
+            Some(unwind) => {
+                interpreter.unwind = Some(unwind);
+                return Value::Null;
+            },
+            None => Value::Null,
+        };
+
+        // `init` always hands back the instance it was bound to
+        if self.is_initializer {
+            return self.closure.borrow().get_at(0, 0).1.unwrap();
+        }
+
+        if let Some(return_type) = &self.return_type {
+            if !interpreter.check_type(return_type, &mut value) {
+                return Value::Null;
+            }
+        }
+        value
+    }
+
+    fn name(&self) -> String {
+        self.name.lexeme.clone()
+    }
+}
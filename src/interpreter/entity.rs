@@ -0,0 +1,150 @@
+use std::{cell::RefCell, collections::HashMap, fmt::Debug, rc::Rc};
+
+use crate::{lexer::tokens::Token, parser::ast::{Var, Visibility}, util::Value};
+
+use super::{callable::{Callable, RogueFunction}, environment::{Environment, VarAttrib}, Interpreter};
+
+/// Runtime representation of an `entity` declaration.
+pub struct EntityClass {
+    pub name: Token,
+    pub open: bool,
+    pub parent: Option<Rc<EntityClass>>,
+    fields: Rc<Vec<Var>>,
+    methods: HashMap<String, Rc<RogueFunction>>,
+    visibility: HashMap<String, Visibility>,
+    closure: Rc<RefCell<Environment>>,
+}
+
+impl EntityClass {
+    pub fn new(name: Token, open: bool, parent: Option<Rc<EntityClass>>, fields: Rc<Vec<Var>>, methods: HashMap<String, Rc<RogueFunction>>, visibility: HashMap<String, Visibility>, closure: Rc<RefCell<Environment>>) -> Self {
+        Self { name, open, parent, fields, methods, visibility, closure }
+    }
+
+    /// Finds the entity in this chain that declares `member`, with the member's visibility.
+    /// Members without a declared visibility (such as trait defaults) are public.
+    pub fn find_declaring(entity: &Rc<EntityClass>, member: &str) -> (Rc<EntityClass>, Visibility) {
+        let mut current: Rc<EntityClass> = Rc::clone(entity);
+        loop {
+            if let Some(visibility) = current.visibility.get(member).copied() {
+                return (current, visibility);
+            }
+            match &current.parent {
+                Some(parent) => current = Rc::clone(parent),
+                None => return (Rc::clone(entity), Visibility::Pub),
+            }
+        }
+    }
+
+    /// Whether `name` is this entity or one of its ancestors.
+    pub fn descends_from(&self, name: &str) -> bool {
+        if self.name.lexeme == name {
+            return true;
+        }
+        match &self.parent {
+            Some(parent) => parent.descends_from(name),
+            None => false,
+        }
+    }
+
+    pub fn find_method(&self, name: &str) -> Option<Rc<RogueFunction>> {
+        if let Some(method) = self.methods.get(name) {
+            return Some(Rc::clone(method));
+        }
+
+        match &self.parent {
+            Some(parent) => parent.find_method(name),
+            None => None,
+        }
+    }
+
+    pub fn arity(&self) -> usize {
+        match self.find_method("init") {
+            Some(init) => init.arity(),
+            None => 0,
+        }
+    }
+
+    pub fn instantiate(entity: &Rc<EntityClass>, interpreter: &mut Interpreter, arguments: Vec<Value>) -> Value {
+        let instance: Rc<RefCell<Instance>> = Rc::new(RefCell::new(Instance::new(Rc::clone(entity))));
+        interpreter.initializing.push(Rc::clone(&instance));
+
+        entity.initialize_fields(&instance, interpreter);
+
+        if let Some(init) = entity.find_method("init") {
+            if interpreter.unwind.is_none() {
+                init.bind(&instance).call(interpreter, arguments);
+            }
+        }
+
+        interpreter.initializing.pop();
+        if interpreter.unwind.is_some() {
+            return Value::Null;
+        }
+        Value::Instance(instance)
+    }
+
+    fn initialize_fields(&self, instance: &Rc<RefCell<Instance>>, interpreter: &mut Interpreter) {
+        // inherited fields first, so a child can rely on them in its own initializers
+        if let Some(parent) = &self.parent {
+            parent.initialize_fields(instance, interpreter);
+            if interpreter.unwind.is_some() {
+                return;
+            }
+        }
+
+        // field initializers see `this` just like method bodies do
+        let mut scope: Environment = Environment::with_enclosing(Rc::clone(&self.closure));
+        scope.define("this".to_string(), VarAttrib(None, Some(Value::Instance(Rc::clone(instance))), false));
+        let scope: Rc<RefCell<Environment>> = Rc::new(RefCell::new(scope));
+
+        for field in self.fields.iter() {
+            let mut value: Option<Value> = None;
+            if let Some(expr) = &field.expr {
+                value = Some(interpreter.evaluate_in(&**expr, Rc::clone(&scope)));
+            }
+            if interpreter.unwind.is_some() || !interpreter.match_types(field, &mut value) {
+                return;
+            }
+
+            instance.borrow_mut().fields.insert(field.name.lexeme.clone(), VarAttrib(field.datatype.clone(), value, field.mutability));
+        }
+    }
+}
+
+impl Debug for EntityClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<entity {}>", self.name.lexeme)
+    }
+}
+
+#[derive(Debug)]
+pub struct Instance {
+    pub entity: Rc<EntityClass>,
+    pub fields: HashMap<String, VarAttrib>,
+}
+
+impl Instance {
+    pub fn new(entity: Rc<EntityClass>) -> Self {
+        Self { entity, fields: HashMap::new() }
+    }
+}
+
+/// Runtime representation of a `trait` declaration.
+pub struct RogueTrait {
+    pub name: Token,
+    // name and parameter count of every method an entity has to provide
+    pub required: Vec<(Token, usize)>,
+    pub defaults: HashMap<String, Rc<RogueFunction>>,
+}
+
+impl RogueTrait {
+    pub fn new(name: Token, required: Vec<(Token, usize)>, defaults: HashMap<String, Rc<RogueFunction>>) -> Self {
+        Self { name, required, defaults }
+    }
+}
+
+impl Debug for RogueTrait {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<trait {}>", self.name.lexeme)
+    }
+}
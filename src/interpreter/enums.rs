@@ -0,0 +1,103 @@
+use std::{fmt::Debug, rc::Rc};
+
+use crate::{lexer::tokens::Token, parser::ast::EnumVariant, util::Value};
+
+use super::{callable::Callable, Interpreter};
+
+/// Runtime representation of an `enum` declaration.
+pub struct RogueEnum {
+    pub name: Token,
+    variants: Rc<Vec<EnumVariant>>,
+}
+
+impl RogueEnum {
+    pub fn new(name: Token, variants: Rc<Vec<EnumVariant>>) -> Self {
+        Self { name, variants }
+    }
+
+    /// `Enum.Variant`: the value itself for variants without payload,
+    /// otherwise a constructor taking the payload.
+    pub fn variant(enum_: &Rc<RogueEnum>, name: &str) -> Option<Value> {
+        let index: usize = enum_.variants.iter().position(|variant| variant.name.lexeme == name)?;
+
+        if enum_.variants[index].fields.is_empty() {
+            return Some(Value::Variant(Rc::new(VariantValue::new(Rc::clone(enum_), index, Vec::new()))));
+        }
+        Some(Value::Callable(Rc::new(VariantConstructor { enum_: Rc::clone(enum_), index })))
+    }
+}
+
+impl Debug for RogueEnum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<enum {}>", self.name.lexeme)
+    }
+}
+
+#[derive(Debug)]
+pub struct VariantValue {
+    pub enum_: Rc<RogueEnum>,
+    index: usize,
+    pub values: Vec<Value>,
+}
+
+impl VariantValue {
+    pub fn new(enum_: Rc<RogueEnum>, index: usize, values: Vec<Value>) -> Self {
+        Self { enum_, index, values }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.enum_.variants[self.index].name.lexeme
+    }
+
+    /// Whether both values were built by the same variant of the same enum, payloads aside.
+    pub fn same_variant(&self, other: &VariantValue) -> bool {
+        Rc::ptr_eq(&self.enum_, &other.enum_) && self.index == other.index
+    }
+
+    /// Payload value by the field name given in the enum declaration.
+    pub fn field(&self, name: &str) -> Option<Value> {
+        let fields = &self.enum_.variants[self.index].fields;
+        let index: usize = fields.iter().position(|field| field.name.lexeme == name)?;
+        Some(self.values[index].clone())
+    }
+}
+
+impl core::fmt::Display for VariantValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.enum_.name.lexeme, self.name())?;
+
+        if !self.values.is_empty() {
+            let values: Vec<String> = self.values.iter().map(|value| value.to_string()).collect();
+            write!(f, "({})", values.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct VariantConstructor {
+    enum_: Rc<RogueEnum>,
+    index: usize,
+}
+
+impl Callable for VariantConstructor {
+    fn arity(&self) -> usize {
+        self.enum_.variants[self.index].fields.len()
+    }
+
+    fn call(&self, interpreter: &mut Interpreter, mut arguments: Vec<Value>) -> Value {
+        for (field, argument) in self.enum_.variants[self.index].fields.iter().zip(&mut arguments) {
+            if let Some(datatype) = &field.datatype {
+                if !interpreter.check_type(datatype, argument) {
+                    return Value::Null;
+                }
+            }
+        }
+
+        Value::Variant(Rc::new(VariantValue::new(Rc::clone(&self.enum_), self.index, arguments)))
+    }
+
+    fn name(&self) -> String {
+        format!("{}.{}", self.enum_.name.lexeme, self.enum_.variants[self.index].name.lexeme)
+    }
+}
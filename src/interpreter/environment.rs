@@ -1,5 +1,5 @@
 
-use std::{collections::HashMap};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
 use crate::{lexer::tokens::Token, util::{error::Error, Value}};
 use crate::util::error_formatter::*;
@@ -8,11 +8,13 @@ use crate::util::error_formatter::*;
 //                   datatype         , value            , mutability        
 pub struct VarAttrib(pub Option<Token>, pub Option<Value>, pub bool);
 
-#[derive(Clone, Debug)]
+// not `Clone`: scopes are shared through `Rc<RefCell<Environment>>`, never copied
+#[derive(Debug)]
 pub struct Environment {
+    // globals are looked up by name, locals by the slot the resolver assigned them
     pub map: HashMap<String, VarAttrib>,
-    pub enclosing: Option<Box<Self>>,
-    error_handler: ErrorHandler,
+    pub slots: Vec<VarAttrib>,
+    pub enclosing: Option<Rc<RefCell<Environment>>>,
 }
 
 impl Environment {
@@ -20,55 +22,88 @@ impl Environment {
     pub fn new() -> Self {
         Self {
             map: HashMap::new(),
+            slots: Vec::new(),
             enclosing: None,
-            error_handler: ErrorHandler,
         }
     }
 
-    pub fn with_enclosing(enclosing: Environment) -> Self {
+    /// Creates a new scope that shares (instead of copies) its enclosing scope,
+    /// so closures and nested blocks observe each other's assignments.
+    pub fn with_enclosing(enclosing: Rc<RefCell<Environment>>) -> Self {
         Self {
             map: HashMap::new(),
-            enclosing: Some(Box::new(enclosing)),
-            error_handler: ErrorHandler
+            slots: Vec::new(),
+            enclosing: Some(enclosing),
         }
     }
 
-    pub fn add_scope(&mut self, enclosing: Environment) {
-        self.enclosing = Some(Box::new(enclosing));
+    pub fn define(&mut self, name: String, var_attrib: VarAttrib) {
+        if self.enclosing.is_none() {
+            self.map.insert(name, var_attrib);
+        } else {
+            self.slots.push(var_attrib);
+        }
     }
 
-    pub fn define(&mut self, name: String, var_attrib: VarAttrib) {
-        self.map.insert(name, var_attrib);
+    pub fn get_at(&self, depth: usize, slot: usize) -> VarAttrib {
+        if depth == 0 {
+            return self.slots[slot].clone();
+        }
+        self.enclosing.as_ref().expect("Binding deeper than scope chain").borrow().get_at(depth - 1, slot)
     }
 
-    pub fn get(&self, name: Token) -> VarAttrib {
+    pub fn assign_at(&mut self, depth: usize, slot: usize, name: Token, value: &Value) -> Result<(), ErrorKind> {
+        if depth > 0 {
+            return self.enclosing.as_ref().expect("Binding deeper than scope chain").borrow_mut().assign_at(depth - 1, slot, name, value);
+        }
+
+        let var_attrib: &mut VarAttrib = &mut self.slots[slot];
+        if !var_attrib.2 {
+            return Err(ErrorKind::ImmutableVar(name.clone(), name.lexeme));
+        }
+        var_attrib.1 = Some(fitted(&var_attrib.0, &name, value)?);
+        Ok(())
+    }
+
+    pub fn get(&self, name: Token) -> Result<VarAttrib, ErrorKind> {
         if self.map.contains_key(&name.lexeme) {
-            return self.map.get(&name.lexeme).unwrap().clone();
+            return Ok(self.map.get(&name.lexeme).unwrap().clone());
         }
 
         if let Some(n) = &self.enclosing {
-            return n.get(name);
+            return n.borrow().get(name);
         }
 
-        self.error_handler.throw(ErrorKind::UndefinedVar(name));
+        Err(ErrorKind::UndefinedVar(name))
     } 
 
-    pub fn assign(&mut self, name: Token, value: &Value) {
+    pub fn assign(&mut self, name: Token, value: &Value) -> Result<(), ErrorKind> {
         if self.map.contains_key(&name.lexeme) {
             let var_attrib: &VarAttrib = self.map.get(&name.lexeme).unwrap();
             if var_attrib.2 == true {
-                self.map.insert(name.lexeme, VarAttrib(var_attrib.0.clone(), Some(value.clone()), var_attrib.2));
-                return;
+                let value: Value = fitted(&var_attrib.0, &name, value)?;
+                self.map.insert(name.lexeme, VarAttrib(var_attrib.0.clone(), Some(value), var_attrib.2));
+                return Ok(());
             } else {
-                self.error_handler.throw(ErrorKind::ImmutableVar(name.clone(), name.lexeme));
+                return Err(ErrorKind::ImmutableVar(name.clone(), name.lexeme));
             }
         }
 
-        if let Some(mut n) = self.enclosing.clone() {
-            n.assign(name, value);
-            return;
+        if let Some(n) = &self.enclosing {
+            return n.borrow_mut().assign(name, value);
         }
 
-        self.error_handler.throw(ErrorKind::UndefinedVar(name.clone()));
+        Err(ErrorKind::UndefinedVar(name.clone()))
     }
-}
\ No newline at end of file
+}
+
+/// Assigned values keep the width of a typed variable.
+fn fitted(datatype: &Option<Token>, name: &Token, value: &Value) -> Result<Value, ErrorKind> {
+    let Some(datatype) = datatype else {
+        return Ok(value.clone());
+    };
+
+    // errors point at the assignment, not at the declaration
+    let datatype: Token = Token::new(datatype.type_, datatype.lexeme.clone(), None, name.line, name.row);
+    super::fit_type(&datatype, value)
+}
@@ -0,0 +1,39 @@
+use crate::util::{error_formatter::{Diagnostic, ErrorHandler, ErrorKind}, Value};
+
+/// An error travelling up the call stack until a `catch` takes it.
+#[derive(Debug)]
+pub struct RogueError {
+    pub message: String,
+    pub line: usize,
+    pub row: usize,
+    // what `throw` was given, runtime errors carry their message
+    pub value: Value,
+    // reported through the `ErrorHandler` if nothing catches the error
+    pub kind: ErrorKind,
+}
+
+impl RogueError {
+    pub fn new(error_handler: &ErrorHandler, kind: ErrorKind, value: Option<Value>) -> Self {
+        let diagnostic: Diagnostic = error_handler.diagnose(kind.clone());
+        let value: Value = value.unwrap_or_else(|| Value::String(diagnostic.description.clone()));
+
+        Self { message: diagnostic.description, line: diagnostic.line, row: diagnostic.row, value, kind }
+    }
+
+    /// `e.message`, `e.line`, `e.row` and `e.value` inside a `catch` block.
+    pub fn field(&self, name: &str) -> Option<Value> {
+        match name {
+            "message" => Some(Value::String(self.message.clone())),
+            "line" => Some(Value::Integer(self.line as i32)),
+            "row" => Some(Value::Integer(self.row as i32)),
+            "value" => Some(self.value.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl core::fmt::Display for RogueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} [line {}, row {}]", self.message, self.line, self.row)
+    }
+}
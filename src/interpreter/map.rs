@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+
+use crate::util::Value;
+
+use super::numeric;
+
+/// The values that can key a map, integers of every width key the same entry as long as they are equal.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MapKey {
+    Integer(i128),
+    String(String),
+    Boolean(bool),
+}
+
+impl MapKey {
+    pub fn from_value(value: &Value) -> Option<MapKey> {
+        match value {
+            Value::String(s) => Some(MapKey::String(s.clone())),
+            Value::Boolean(b) => Some(MapKey::Boolean(*b)),
+            _ => numeric::integer(value).map(MapKey::Integer),
+        }
+    }
+
+    pub fn to_value(&self) -> Value {
+        match self {
+            // every key came from an integer of at most 64 bits
+            MapKey::Integer(n) => numeric::unsuffixed(*n).unwrap(),
+            MapKey::String(s) => Value::String(s.clone()),
+            MapKey::Boolean(b) => Value::Boolean(*b),
+        }
+    }
+}
+
+/// Runtime value of `{ key: value }`, entries keep the order they were first inserted in
+/// so printing and iterating a map is reproducible.
+#[derive(Debug, Default)]
+pub struct RogueMap {
+    entries: Vec<(MapKey, Value)>,
+    positions: HashMap<MapKey, usize>,
+}
+
+impl RogueMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, key: &MapKey) -> Option<Value> {
+        self.positions.get(key).map(|position| self.entries[*position].1.clone())
+    }
+
+    pub fn contains_key(&self, key: &MapKey) -> bool {
+        self.positions.contains_key(key)
+    }
+
+    /// Overwriting a key keeps its original position.
+    pub fn insert(&mut self, key: MapKey, value: Value) {
+        match self.positions.get(&key) {
+            Some(position) => self.entries[*position].1 = value,
+            None => {
+                self.positions.insert(key.clone(), self.entries.len());
+                self.entries.push((key, value));
+            },
+        }
+    }
+
+    pub fn keys(&self) -> Vec<Value> {
+        self.entries.iter().map(|(key, _)| key.to_value()).collect()
+    }
+}
+
+impl core::fmt::Display for RogueMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let entries: Vec<String> = self.entries.iter().map(|(key, value)| format!("{}: {value}", key.to_value())).collect();
+        write!(f, "{{{}}}", entries.join(", "))
+    }
+}
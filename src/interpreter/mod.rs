@@ -1,22 +1,58 @@
-use std::{any::{type_name_of_val, TypeId}, collections::HashMap, io};
+use std::{any::{type_name_of_val, Any, TypeId}, collections::HashMap, io, cell::{Ref, RefCell}, rc::Rc};
 
+use callable::{Callable, RogueFunction};
+use entity::{EntityClass, Instance, RogueTrait};
+use enums::RogueEnum;
 use environment::{Environment, VarAttrib};
+use exception::RogueError;
+use map::{MapKey, RogueMap};
+use numeric::NumericType;
+use range::RogueRange;
 
-use crate::{lexer::tokens::{Token, TokenType}, parser::ast::{Expr, Stmt, Visitor}, util::error_formatter::{ErrorHandler, ErrorKind}, /*util::{downcast_obj, downcast_to, downcast_to_f64, Number, Object*/};
+use crate::{lexer::tokens::{Token, TokenType}, parser::ast::{Expr, Stmt, VarPattern, Visibility, Visitor}, util::error_formatter::{ErrorHandler, ErrorKind}, /*util::{downcast_obj, downcast_to, downcast_to_f64, Number, Object*/};
 use crate::util::{Value, error::Error};
 
+pub mod callable;
+pub mod entity;
+pub mod enums;
 pub mod environment;
+pub mod exception;
+pub mod map;
+pub mod numeric;
+pub mod range;
+
+/// Control flow that leaves the statement currently being executed.
+#[derive(Debug)]
+pub enum Unwind {
+    Return(Value),
+    Throw(Rc<RogueError>),
+    // the label of the loop to leave or continue, `None` targets the innermost loop
+    Break(Option<String>),
+    Continue(Option<String>),
+}
 
 pub struct Interpreter {
-    environment: Environment,
+    globals: Rc<RefCell<Environment>>,
+    environment: Rc<RefCell<Environment>>,
+    unwind: Option<Unwind>,
+    // statements deferred by each running block, innermost block last
+    deferred: Vec<Vec<Rc<dyn Stmt>>>,
+    // instances whose field initializers or `init` are running, innermost last;
+    // only the innermost one may still assign to its immutable fields
+    initializing: Vec<Rc<RefCell<Instance>>>,
     error_handler: ErrorHandler
 }
 
 impl Interpreter {
 
     pub fn new() -> Self {
+        let globals: Rc<RefCell<Environment>> = Rc::new(RefCell::new(Environment::new()));
         Self {
-            environment: Environment::new(),
+            environment: Rc::clone(&globals),
+            globals,
+            unwind: None,
+            deferred: Vec::new(),
+            initializing: Vec::new(),
             error_handler: ErrorHandler
         }
     }
@@ -25,6 +61,13 @@ impl Interpreter {
         return expr.accept(self);
     }
 
+    fn evaluate_in(&mut self, expr: &dyn Expr, environment: Rc<RefCell<Environment>>) -> Value {
+        let previous: Rc<RefCell<Environment>> = std::mem::replace(&mut self.environment, environment);
+        let value: Value = self.evaluate(expr);
+        self.environment = previous;
+        value
+    }
+
     fn is_truthy(&self, object: Value) -> bool {
         if let Value::Boolean(b) = object {
             return b;
@@ -33,25 +76,224 @@ impl Interpreter {
     }
 
     pub fn interpret(&mut self, stmts: Vec<Box<dyn Stmt>>) {
+        self.deferred.push(Vec::new());
         for stmt in stmts {
             self.execute(stmt);
+            if self.unwind.is_some() {
+                break;
+            }
+        }
+        self.run_deferred();
+
+        if let Some(Unwind::Throw(error)) = self.unwind.take() {
+            self.error_handler.throw(error.kind.clone());
         }
     }
 
+    /// Starts unwinding with `error` until a `catch` takes it, or `interpret` reports it.
+    /// The returned `Null` stands in for the value the failed expression never produced.
+    fn raise(&mut self, error: ErrorKind) -> Value {
+        if self.unwind.is_none() {
+            let error: RogueError = RogueError::new(&self.error_handler, error, None);
+            self.unwind = Some(Unwind::Throw(Rc::new(error)));
+        }
+        Value::Null
+    }
+
     fn execute(&mut self, stmt: Box<dyn Stmt>) {
         stmt.accept(self);
     }
 
-    fn execute_block(&mut self, statements: &Vec<Box<dyn Stmt>>, environment: Environment) {
-        let previous: Environment = self.environment.clone();
-        self.environment = environment;
+    fn check_access(&mut self, entity: &Rc<EntityClass>, member: &Token, accessor: Option<&String>) -> bool {
+        let (owner, visibility) = EntityClass::find_declaring(entity, &member.lexeme);
+
+        let allowed: bool = match (visibility, accessor) {
+            (Visibility::Pub, _) => true,
+            (_, None) => false,
+            (Visibility::Priv, Some(accessor)) => owner.name.lexeme == *accessor,
+            // the accessing entity has to sit between the instance's entity and the owner
+            (Visibility::Prot, Some(accessor)) => entity.descends_from(accessor) && (owner.name.lexeme == *accessor || !owner.descends_from(accessor)),
+        };
+
+        if !allowed {
+            self.raise(ErrorKind::InaccessibleMember(member.clone(), owner.name.lexeme.clone(), visibility));
+        }
+        allowed
+    }
+
+    fn execute_block(&mut self, statements: &[Box<dyn Stmt>], environment: Environment) {
+        let previous: Rc<RefCell<Environment>> = Rc::clone(&self.environment);
+        self.environment = Rc::new(RefCell::new(environment));
 
+        self.deferred.push(Vec::new());
         for statement in statements {
             statement.accept(self);
+            if self.unwind.is_some() {
+                break;
+            }
         }
+        self.run_deferred();
 
         self.environment = previous;
     }
+
+    /// `string as type_`, parsing a decimal number out of the string.
+    fn parse_number(&mut self, cast: &crate::parser::ast::Cast, string: &str, type_: NumericType) -> Value {
+        let trimmed: &str = string.trim();
+        if type_.is_float() {
+            return match trimmed.parse::<f64>() {
+                Ok(x) => numeric::from_float(x, type_),
+                Err(_) => self.raise(ErrorKind::InvalidCast(cast.keyword.clone(), Value::String(string.to_string()), cast.type_.lexeme.clone())),
+            };
+        }
+
+        match trimmed.parse::<i128>() {
+            Ok(n) => match numeric::from_integer(n, type_) {
+                Some(number) => number,
+                None => self.raise(ErrorKind::OutOfRange(cast.keyword.clone(), trimmed.to_string(), type_.name())),
+            },
+            Err(_) => self.raise(ErrorKind::InvalidCast(cast.keyword.clone(), Value::String(string.to_string()), cast.type_.lexeme.clone())),
+        }
+    }
+
+    /// Arithmetic that fails with an `IntegerOverflow` instead of wrapping around.
+    fn integer_binary(&mut self, operator: &Token, x: i128, y: i128, type_: NumericType) -> Value {
+        let divides: bool = matches!(operator.type_, TokenType::Slash | TokenType::SlashSlash | TokenType::Percent);
+        if divides && y == 0 {
+            return self.raise(ErrorKind::DivisionByZero(operator.clone()));
+        }
+
+        let result: Option<i128> = match operator.type_ {
+            // arithmetic
+            TokenType::Minus => x.checked_sub(y),
+            TokenType::Plus => x.checked_add(y),
+            TokenType::Star => x.checked_mul(y),
+            // `/` truncates toward zero, `//` rounds toward negative infinity
+            TokenType::Slash => x.checked_div(y),
+            TokenType::SlashSlash => x.checked_div(y).map(|q| if x % y != 0 && (x < 0) != (y < 0) { q - 1 } else { q }),
+            TokenType::Percent => Some(x % y),
+            // comparison
+            TokenType::Greater => return Value::Boolean(x > y),
+            TokenType::GreaterEqual => return Value::Boolean(x >= y),
+            TokenType::Less => return Value::Boolean(x < y),
+            TokenType::LessEqual => return Value::Boolean(x <= y),
+            // else
+            _ => return self.raise(ErrorKind::UnkownToken(operator.clone())),
+        };
+
+        match result.and_then(|n| numeric::from_integer(n, type_)) {
+            Some(n) => n,
+            None => self.raise(ErrorKind::IntegerOverflow(operator.clone(), format!("{x} {} {y}", operator.lexeme), type_.name())),
+        }
+    }
+
+    /// `+` concatenates, `*` repeats a string a number of times and the comparisons are lexicographic.
+    fn string_binary(&mut self, operator: &Token, lhs: Value, rhs: Value) -> Value {
+        match (operator.type_, &lhs, &rhs) {
+            (TokenType::Plus, Value::String(x), Value::String(y)) => Value::String(format!("{x}{y}")),
+            (TokenType::Star, Value::String(string), count) | (TokenType::Star, count, Value::String(string)) if numeric::integer(count).is_some() => {
+                let count: i128 = numeric::integer(count).unwrap();
+                match usize::try_from(count) {
+                    Ok(count) => Value::String(string.repeat(count)),
+                    Err(_) => self.raise(ErrorKind::NegativeRepeat(operator.clone(), count)),
+                }
+            },
+            (TokenType::Greater, Value::String(x), Value::String(y)) => Value::Boolean(x > y),
+            (TokenType::GreaterEqual, Value::String(x), Value::String(y)) => Value::Boolean(x >= y),
+            (TokenType::Less, Value::String(x), Value::String(y)) => Value::Boolean(x < y),
+            (TokenType::LessEqual, Value::String(x), Value::String(y)) => Value::Boolean(x <= y),
+            _ => self.raise(ErrorKind::InvalidOperands(operator.clone(), lhs, rhs)),
+        }
+    }
+
+    fn float_binary(&mut self, operator: &Token, x: f64, y: f64, type_: NumericType) -> Value {
+        let divides: bool = matches!(operator.type_, TokenType::Slash | TokenType::SlashSlash | TokenType::Percent);
+        if divides && y == 0.0 {
+            return self.raise(ErrorKind::DivisionByZero(operator.clone()));
+        }
+
+        match operator.type_ {
+            // arithmetic
+            TokenType::Minus => numeric::from_float(x - y, type_),
+            TokenType::Plus => numeric::from_float(x + y, type_),
+            TokenType::Star => numeric::from_float(x * y, type_),
+            TokenType::Slash => numeric::from_float(x / y, type_),
+            TokenType::SlashSlash => numeric::from_float((x / y).floor(), type_),
+            TokenType::Percent => numeric::from_float(x % y, type_),
+            // comparison
+            TokenType::Greater => Value::Boolean(x > y),
+            TokenType::GreaterEqual => Value::Boolean(x >= y),
+            TokenType::Less => Value::Boolean(x < y),
+            TokenType::LessEqual => Value::Boolean(x <= y),
+            // else
+            _ => self.raise(ErrorKind::UnkownToken(operator.clone())),
+        }
+    }
+
+    /// `needle in haystack`: a key of a map, an element of an array or a substring of a string.
+    fn contains(&mut self, operator: &Token, needle: Value, haystack: Value) -> Value {
+        match (&haystack, &needle) {
+            (Value::Map(map), needle) => {
+                let found: bool = MapKey::from_value(needle).is_some_and(|key| map.borrow().contains_key(&key));
+                Value::Boolean(found)
+            },
+            (Value::Array(array), needle) => Value::Boolean(array.borrow().iter().any(|element| element.equals(needle))),
+            (Value::String(string), Value::String(needle)) => Value::Boolean(string.contains(needle.as_str())),
+            _ => self.raise(ErrorKind::NotAContainer(operator.clone(), haystack)),
+        }
+    }
+
+    /// Runs one pass of a `for` body with the loop variable bound to `item` in a fresh scope.
+    /// Returns false once the loop has to stop.
+    fn run_iteration(&mut self, for_: &crate::parser::ast::For, item: Value) -> bool {
+        let mut environment: Environment = Environment::with_enclosing(Rc::clone(&self.environment));
+        if let Some(pattern) = &for_.pattern {
+            if !self.bind_pattern(&mut environment, pattern, item) {
+                return false;
+            }
+        }
+
+        self.execute_block(std::slice::from_ref(&for_.body), environment);
+        self.loop_control(&for_.label)
+    }
+
+    /// Consumes a `break` or `continue` aimed at the loop labeled `label`.
+    /// Returns false once that loop has to stop.
+    fn loop_control(&mut self, label: &Option<Token>) -> bool {
+        let targets = |target: &Option<String>| match target {
+            None => true,
+            Some(target) => label.as_ref().is_some_and(|label| label.lexeme == *target),
+        };
+
+        match &self.unwind {
+            None => true,
+            Some(Unwind::Continue(target)) if targets(target) => {
+                self.unwind = None;
+                true
+            },
+            Some(Unwind::Break(target)) if targets(target) => {
+                self.unwind = None;
+                false
+            },
+            // anything else is meant for an outer loop or function
+            Some(_) => false,
+        }
+    }
+
+    /// Runs the innermost block's deferred statements in reverse order, also while
+    /// a `return` or an error is unwinding through it.
+    fn run_deferred(&mut self) {
+        let deferred: Vec<Rc<dyn Stmt>> = self.deferred.pop().unwrap_or_default();
+
+        for statement in deferred.iter().rev() {
+            let pending: Option<Unwind> = self.unwind.take();
+            statement.accept(self);
+            // an error raised by the deferred statement replaces whatever was unwinding
+            if self.unwind.is_none() {
+                self.unwind = pending;
+            }
+        }
+    }
 }
 
 
@@ -61,12 +303,28 @@ impl Visitor for Interpreter {
         return literal.value.clone().expect("no literal expr found");
     }
 
+    fn visit_interpolation(&mut self, interpolation: &crate::parser::ast::Interpolation) -> Value {
+        let mut string: String = String::new();
+        for part in &interpolation.parts {
+            let value: Value = self.evaluate(&**part);
+            if self.unwind.is_some() {
+                return Value::Null;
+            }
+            string.push_str(&value.to_string());
+        }
+
+        Value::String(string)
+    }
+
     fn visit_grouping(&mut self, grouping: &crate::parser::ast::Grouping) -> Value {
         return self.evaluate(&*grouping.expression);
     }
 
     fn visit_logical(&mut self, logical: &crate::parser::ast::Logical) -> Value {
         let left: Value = self.evaluate(&*logical.lhs);
+        if self.unwind.is_some() {
+            return Value::Null;
+        }
 
         if logical.op.type_ == TokenType::Or {
             if self.is_truthy(left.clone()) {
@@ -83,80 +341,505 @@ impl Visitor for Interpreter {
 
     fn visit_unary(&mut self, unary: &crate::parser::ast::Unary) -> Value {
         let right: Value = self.evaluate(&*unary.right);
+        if self.unwind.is_some() {
+            return Value::Null;
+        }
 
         match unary.operator.type_ {
             TokenType::Minus => {
-                if let Value::Float(value) = right {
-                    return Value::Float(-value);
+                let Some(type_) = NumericType::of(&right) else {
+                    return self.raise(ErrorKind::NumberOperand(unary.operator.clone()));
+                };
+                if type_.is_float() {
+                    return numeric::from_float(-numeric::float(&right).unwrap(), type_);
+                }
+
+                let value: i128 = numeric::integer(&right).unwrap();
+                match numeric::from_integer(-value, type_) {
+                    Some(value) => value,
+                    None => self.raise(ErrorKind::IntegerOverflow(unary.operator.clone(), format!("-({value})"), type_.name())),
                 }
-                self.error_handler.throw(ErrorKind::NumberOperand(unary.operator.clone()));
             },
             TokenType::Bang => {
                 return Value::Boolean(!self.is_truthy(right));
             },
-            _ => self.error_handler.throw(ErrorKind::UnkownToken(unary.operator.clone())),
+            TokenType::Typeof => Value::String(right.type_name()),
+            TokenType::Sizeof => {
+                // the length of a container, or how many bytes a number takes up
+                let size: usize = match &right {
+                    Value::String(string) => string.chars().count(),
+                    Value::Array(array) => array.borrow().len(),
+                    Value::Map(map) => map.borrow().keys().len(),
+                    Value::Tuple(tuple) => tuple.len(),
+                    _ => match NumericType::of(&right) {
+                        Some(numeric) => numeric.bits() as usize / 8,
+                        None => return self.raise(ErrorKind::NoSize(unary.operator.clone(), right)),
+                    },
+                };
+                Value::Integer(size as i32)
+            },
+            TokenType::Nameof => {
+                // the parser only lets variables and properties through, evaluating them made sure they exist
+                let any: &dyn Any = unary.right.as_any();
+                match any.downcast_ref::<crate::parser::ast::Variable>() {
+                    Some(variable) => Value::String(variable.name.lexeme.clone()),
+                    None => Value::String(any.downcast_ref::<crate::parser::ast::Get>().expect("`nameof` takes a variable or property").name.lexeme.clone()),
+                }
+            },
+            _ => self.raise(ErrorKind::UnkownToken(unary.operator.clone())),
+        }
+    }
+
+    fn visit_cast(&mut self, cast: &crate::parser::ast::Cast) -> Value {
+        let value: Value = self.evaluate(&*cast.value);
+        if self.unwind.is_some() {
+            return Value::Null;
+        }
+
+        if let Some(type_) = NumericType::from_name(&cast.type_.lexeme) {
+            return match &value {
+                Value::Boolean(b) => numeric::from_integer(*b as i128, type_).unwrap(),
+                Value::String(string) => self.parse_number(cast, string, type_),
+                _ => match numeric::saturate(&value, type_) {
+                    Some(number) => number,
+                    None => self.raise(ErrorKind::InvalidCast(cast.keyword.clone(), value, cast.type_.lexeme.clone())),
+                },
+            };
+        }
+
+        match (cast.type_.lexeme.as_str(), &value) {
+            ("string" | "String", _) => Value::String(value.to_string()),
+            ("boolean", Value::Boolean(_)) => value,
+            ("boolean", Value::String(string)) if string == "true" || string == "false" => Value::Boolean(string == "true"),
+            ("boolean", _) if NumericType::of(&value).is_some() => Value::Boolean(numeric::float(&value) != Some(0.0)),
+            _ => self.raise(ErrorKind::InvalidCast(cast.keyword.clone(), value, cast.type_.lexeme.clone())),
         }
     }
 
     fn visit_binary(&mut self, binary: &crate::parser::ast::Binary) -> Value {
         let lhs: Value = self.evaluate(&*binary.left);
+        if self.unwind.is_some() {
+            return Value::Null;
+        }
         let rhs: Value = self.evaluate(&*binary.right);
+        if self.unwind.is_some() {
+            return Value::Null;
+        }
 
-        let x: f64 = match lhs {
-            Value::Float(n) => n,
-            Value::Integer(n) => n as f64,
-            _ => panic!()
-        };
-        let y: f64 = match rhs {
-            Value::Float(n) => n,
-            Value::Integer(n) => n as f64,
-            _ => panic!()
+        if binary.operator.type_ == TokenType::In {
+            return self.contains(&binary.operator, lhs, rhs);
+        }
+
+        match binary.operator.type_ {
+            TokenType::EqualEqual => return Value::Boolean(lhs.equals(&rhs)),
+            TokenType::BangEqual => return Value::Boolean(!lhs.equals(&rhs)),
+            _ => {},
+        }
+
+        if let (Value::String(_), _) | (_, Value::String(_)) = (&lhs, &rhs) {
+            return self.string_binary(&binary.operator, lhs, rhs);
+        }
+        let (Some(x), Some(y)) = (NumericType::of(&lhs), NumericType::of(&rhs)) else {
+            return self.raise(ErrorKind::InvalidOperands(binary.operator.clone(), lhs, rhs));
         };
 
-        return match binary.operator.type_ {
-            // arithmetic
-            TokenType::Minus => Value::Float(x-y),
-            TokenType::Plus => Value::Float(x+y),
-            TokenType::Slash => Value::Float(x/y),
-            TokenType::Star => Value::Float(x*y),
-            // comparison
-            TokenType::Greater => Value::Boolean(x>y),
-            TokenType::GreaterEqual => Value::Boolean(x>=y),
-            TokenType::Less => Value::Boolean(x<y),
-            TokenType::LessEqual => Value::Boolean(x<=y),
-            TokenType::EqualEqual => Value::Boolean(x==y),
-            TokenType::BangEqual => Value::Boolean(x!=y),
-            // else
-            _ => self.error_handler.throw(ErrorKind::UnkownToken(binary.operator.clone())),
+        // integers keep their width, mixing widths or kinds computes in the common type, and
+        // an unsuffixed literal takes the type of a number of its own kind, so `1u8 + 255` overflows
+        let adopted: Option<(&Value, NumericType)> = match (untyped(&*binary.left), untyped(&*binary.right)) {
+            (true, false) if x.is_float() == y.is_float() => Some((&lhs, y)),
+            (false, true) if x.is_float() == y.is_float() => Some((&rhs, x)),
+            _ => None,
+        };
+        let type_: NumericType = match adopted {
+            Some((literal, type_)) if numeric::convert(literal, type_).is_none() => {
+                return self.raise(ErrorKind::OutOfRange(binary.operator.clone(), literal.to_string(), type_.name()));
+            },
+            Some((_, type_)) => type_,
+            None => x.common(y),
+        };
+        match type_.is_float() {
+            true => self.float_binary(&binary.operator, numeric::float(&lhs).unwrap(), numeric::float(&rhs).unwrap(), type_),
+            false => self.integer_binary(&binary.operator, numeric::integer(&lhs).unwrap(), numeric::integer(&rhs).unwrap(), type_),
         }
     }
 
-    fn visit_variable(&self, variable: &crate::parser::ast::Variable) -> Value {
-        return self.environment.get(variable.name.clone()).1.unwrap();
+    fn visit_variable(&mut self, variable: &crate::parser::ast::Variable) -> Value {
+        let var_attrib: Result<VarAttrib, ErrorKind> = match variable.binding.get() {
+            Some(binding) => Ok(self.environment.borrow().get_at(binding.depth, binding.slot)),
+            None => self.globals.borrow().get(variable.name.clone()),
+        };
+        match var_attrib {
+            Ok(VarAttrib(_, Some(value), _)) => value,
+            Ok(_) => self.raise(ErrorKind::UninitializedVar(variable.name.clone())),
+            Err(error) => self.raise(error),
+        }
     }
 
     
     fn visit_assign(&mut self, assign: &crate::parser::ast::Assign) -> Value {
         let value: Value = self.evaluate(&*assign.value);
-        self.environment.assign(assign.name.clone(), &value);
+        if self.unwind.is_some() {
+            return Value::Null;
+        }
+
+        let assigned: Result<(), ErrorKind> = match assign.binding.get() {
+            Some(binding) => self.environment.borrow_mut().assign_at(binding.depth, binding.slot, assign.name.clone(), &value),
+            None => self.globals.borrow_mut().assign(assign.name.clone(), &value),
+        };
+        if let Err(error) = assigned {
+            return self.raise(error);
+        }
         return value;
     }
 
+    fn visit_call(&mut self, call: &crate::parser::ast::Call) -> Value {
+        let callee: Value = self.evaluate(&*call.callee);
+
+        let mut arguments: Vec<Value> = Vec::new();
+        for argument in &call.arguments {
+            arguments.push(self.evaluate(&**argument));
+        }
+        if self.unwind.is_some() {
+            return Value::Null;
+        }
+
+        if let Value::Callable(function) = callee {
+            if arguments.len() != function.arity() {
+                return self.raise(ErrorKind::ArityMismatch(call.paren.clone(), function.arity(), arguments.len()));
+            }
+            return function.call(self, arguments);
+        }
+
+        self.raise(ErrorKind::NotCallable(call.paren.clone()))
+    }
+
+    fn visit_array(&mut self, array: &crate::parser::ast::Array) -> Value {
+        let mut elements: Vec<Value> = Vec::new();
+        for element in &array.elements {
+            elements.push(self.evaluate(&**element));
+            if self.unwind.is_some() {
+                return Value::Null;
+            }
+        }
+
+        Value::Array(Rc::new(RefCell::new(elements)))
+    }
+
+    fn visit_tuple(&mut self, tuple: &crate::parser::ast::Tuple) -> Value {
+        let mut elements: Vec<Value> = Vec::new();
+        for element in &tuple.elements {
+            elements.push(self.evaluate(&**element));
+            if self.unwind.is_some() {
+                return Value::Null;
+            }
+        }
+
+        Value::Tuple(Rc::new(elements))
+    }
+
+    fn visit_map(&mut self, map: &crate::parser::ast::Map) -> Value {
+        let mut entries: RogueMap = RogueMap::new();
+        for (key, value) in &map.entries {
+            let key: Value = self.evaluate(&**key);
+            if self.unwind.is_some() {
+                return Value::Null;
+            }
+            let Some(key) = MapKey::from_value(&key) else {
+                return self.raise(ErrorKind::InvalidMapKey(map.brace.clone(), key));
+            };
+
+            let value: Value = self.evaluate(&**value);
+            if self.unwind.is_some() {
+                return Value::Null;
+            }
+            entries.insert(key, value);
+        }
+
+        Value::Map(Rc::new(RefCell::new(entries)))
+    }
+
+    fn visit_index(&mut self, index: &crate::parser::ast::Index) -> Value {
+        let object: Value = self.evaluate(&*index.object);
+        if self.unwind.is_some() {
+            return Value::Null;
+        }
+        let position: Value = self.evaluate(&*index.index);
+        if self.unwind.is_some() {
+            return Value::Null;
+        }
+
+        if let Value::Map(map) = &object {
+            let Some(key) = MapKey::from_value(&position) else {
+                return self.raise(ErrorKind::InvalidMapKey(index.bracket.clone(), position));
+            };
+            let value: Option<Value> = map.borrow().get(&key);
+            return match value {
+                Some(value) => value,
+                None => self.raise(ErrorKind::MissingKey(index.bracket.clone(), position)),
+            };
+        }
+
+        if let Value::Tuple(tuple) = &object {
+            let Some(position) = numeric::integer(&position) else {
+                return self.raise(ErrorKind::InvalidIndex(index.bracket.clone(), position));
+            };
+            return match element_position(position, tuple.len()) {
+                Some(i) => tuple[i].clone(),
+                None => self.raise(ErrorKind::IndexOutOfBounds(index.bracket.clone(), position, tuple.len())),
+            };
+        }
+
+        let Value::Array(array) = object else {
+            return self.raise(ErrorKind::NotIndexable(index.bracket.clone(), object));
+        };
+        let elements: Ref<Vec<Value>> = array.borrow();
+
+        if let Some(position) = numeric::integer(&position) {
+            return match element_position(position, elements.len()) {
+                Some(i) => elements[i].clone(),
+                None => self.raise(ErrorKind::IndexOutOfBounds(index.bracket.clone(), position, elements.len())),
+            };
+        }
+
+        match position {
+            Value::Range(range) => {
+                // negative bounds count from the end just like single indices
+                let len: i128 = elements.len() as i128;
+                let wrap = |bound: i128| if bound < 0 { bound + len } else { bound };
+                let range: RogueRange = RogueRange::new(wrap(range.start), wrap(range.end), range.step, range.inclusive, range.type_);
+
+                let mut slice: Vec<Value> = Vec::new();
+                for position in range.iter() {
+                    match element_position(position, elements.len()).filter(|_| position >= 0) {
+                        Some(i) => slice.push(elements[i].clone()),
+                        None => return self.raise(ErrorKind::IndexOutOfBounds(index.bracket.clone(), position, elements.len())),
+                    }
+                }
+                Value::Array(Rc::new(RefCell::new(slice)))
+            },
+            position => self.raise(ErrorKind::InvalidIndex(index.bracket.clone(), position)),
+        }
+    }
+
+    fn visit_set_index(&mut self, set_index: &crate::parser::ast::SetIndex) -> Value {
+        let object: Value = self.evaluate(&*set_index.object);
+        if self.unwind.is_some() {
+            return Value::Null;
+        }
+        let position: Value = self.evaluate(&*set_index.index);
+        if self.unwind.is_some() {
+            return Value::Null;
+        }
+        let value: Value = self.evaluate(&*set_index.value);
+        if self.unwind.is_some() {
+            return Value::Null;
+        }
+
+        if let Value::Map(map) = &object {
+            let Some(key) = MapKey::from_value(&position) else {
+                return self.raise(ErrorKind::InvalidMapKey(set_index.bracket.clone(), position));
+            };
+            map.borrow_mut().insert(key, value.clone());
+            return value;
+        }
+
+        let Value::Array(array) = object else {
+            return self.raise(ErrorKind::NotIndexable(set_index.bracket.clone(), object));
+        };
+        let Some(position) = numeric::integer(&position) else {
+            return self.raise(ErrorKind::InvalidIndex(set_index.bracket.clone(), position));
+        };
+
+        let len: usize = array.borrow().len();
+        match element_position(position, len) {
+            Some(i) => array.borrow_mut()[i] = value.clone(),
+            None => return self.raise(ErrorKind::IndexOutOfBounds(set_index.bracket.clone(), position, len)),
+        }
+        value
+    }
+
+    fn visit_get(&mut self, get: &crate::parser::ast::Get) -> Value {
+        let object: Value = self.evaluate(&*get.object);
+        if self.unwind.is_some() {
+            return Value::Null;
+        }
+
+        if let Value::Instance(instance) = &object {
+            let entity: Rc<EntityClass> = Rc::clone(&instance.borrow().entity);
+            if !self.check_access(&entity, &get.name, get.accessor.borrow().as_ref()) {
+                return Value::Null;
+            }
+
+            if let Some(field) = instance.borrow().fields.get(&get.name.lexeme) {
+                return field.1.clone().unwrap_or(Value::Null);
+            }
+
+            if let Some(method) = instance.borrow().entity.find_method(&get.name.lexeme) {
+                return Value::Callable(Rc::new(method.bind(instance)));
+            }
+
+            return self.raise(ErrorKind::UndefinedProperty(get.name.clone()));
+        }
+
+        if let Value::Enum(enum_) = &object {
+            return match RogueEnum::variant(enum_, &get.name.lexeme) {
+                Some(variant) => variant,
+                None => self.raise(ErrorKind::UnknownVariant(get.name.clone(), enum_.name.lexeme.clone())),
+            };
+        }
+
+        let field: Option<Value> = match &object {
+            Value::Variant(variant) => variant.field(&get.name.lexeme),
+            Value::Error(error) => error.field(&get.name.lexeme),
+            _ => return self.raise(ErrorKind::NotAnInstance(get.name.clone())),
+        };
+
+        match field {
+            Some(value) => value,
+            None => self.raise(ErrorKind::UndefinedProperty(get.name.clone())),
+        }
+    }
+
+    fn visit_set(&mut self, set: &crate::parser::ast::Set) -> Value {
+        let object: Value = self.evaluate(&*set.object);
+        if self.unwind.is_some() {
+            return Value::Null;
+        }
+
+        let Value::Instance(instance) = object else {
+            return self.raise(ErrorKind::NotAnInstance(set.name.clone()));
+        };
+
+        let entity: Rc<EntityClass> = Rc::clone(&instance.borrow().entity);
+        if !self.check_access(&entity, &set.name, set.accessor.borrow().as_ref()) {
+            return Value::Null;
+        }
+
+        let mut value: Value = self.evaluate(&*set.value);
+        if self.unwind.is_some() {
+            return Value::Null;
+        }
+
+        let field: Option<VarAttrib> = instance.borrow().fields.get(&set.name.lexeme).cloned();
+        let Some(VarAttrib(datatype, _, mutable)) = field else {
+            return self.raise(ErrorKind::UndefinedProperty(set.name.clone()));
+        };
+
+        let initializing: bool = self.initializing.last().is_some_and(|i| Rc::ptr_eq(i, &instance));
+        if !mutable && !initializing {
+            return self.raise(ErrorKind::ImmutableVar(set.name.clone(), set.name.lexeme.clone()));
+        }
+        if let Some(datatype) = &datatype {
+            if !self.check_type(datatype, &mut value) {
+                return Value::Null;
+            }
+        }
+
+        instance.borrow_mut().fields.insert(set.name.lexeme.clone(), VarAttrib(datatype, Some(value.clone()), mutable));
+        value
+    }
+
+    fn visit_this(&mut self, this: &crate::parser::ast::This) -> Value {
+        let binding: crate::parser::ast::Binding = this.binding.get().expect("`this` is resolved inside entities");
+        return self.environment.borrow().get_at(binding.depth, binding.slot).1.unwrap();
+    }
+
+    fn visit_new(&mut self, new: &crate::parser::ast::New) -> Value {
+        let entity: Value = self.evaluate(&*new.entity);
+
+        let mut arguments: Vec<Value> = Vec::new();
+        for argument in &new.arguments {
+            arguments.push(self.evaluate(&**argument));
+        }
+        if self.unwind.is_some() {
+            return Value::Null;
+        }
+
+        if let Value::Entity(entity) = entity {
+            if arguments.len() != entity.arity() {
+                return self.raise(ErrorKind::ArityMismatch(new.keyword.clone(), entity.arity(), arguments.len()));
+            }
+            return EntityClass::instantiate(&entity, self, arguments);
+        }
+
+        self.raise(ErrorKind::NotAnEntity(new.keyword.clone()))
+    }
+
+    fn visit_parent(&mut self, parent: &crate::parser::ast::Parent) -> Value {
+        let binding: crate::parser::ast::Binding = parent.binding.get().expect("`parent` is resolved inside subentities");
+
+        let Value::Entity(parent_entity) = self.environment.borrow().get_at(binding.depth, binding.slot).1.unwrap() else {
+            self.error_handler.throw(ErrorKind::Fatal);
+        };
+        // `this` is always bound one scope below `parent`
+        let Value::Instance(instance) = self.environment.borrow().get_at(binding.depth - 1, 0).1.unwrap() else {
+            self.error_handler.throw(ErrorKind::Fatal);
+        };
+
+        // `parent` is only usable from subentities, so only private methods are off limits
+        let (owner, visibility) = EntityClass::find_declaring(&parent_entity, &parent.method.lexeme);
+        if visibility == Visibility::Priv {
+            return self.raise(ErrorKind::InaccessibleMember(parent.method.clone(), owner.name.lexeme.clone(), visibility));
+        }
+
+        match parent_entity.find_method(&parent.method.lexeme) {
+            Some(method) => Value::Callable(Rc::new(method.bind(&instance))),
+            None => self.raise(ErrorKind::UndefinedProperty(parent.method.clone())),
+        }
+    }
+
+    fn visit_range(&mut self, range: &crate::parser::ast::Range) -> Value {
+        let mut bounds: Vec<i128> = Vec::new();
+        let mut types: Vec<NumericType> = Vec::new();
+        for expr in [Some(&range.start), Some(&range.end), range.step.as_ref()].into_iter().flatten() {
+            let value: Value = self.evaluate(&**expr);
+            if self.unwind.is_some() {
+                return Value::Null;
+            }
+
+            let (Some(n), Some(type_)) = (numeric::integer(&value), NumericType::of(&value)) else {
+                return self.raise(ErrorKind::RangeBound(range.operator.clone(), value));
+            };
+            bounds.push(n);
+            types.push(type_);
+        }
+
+        let step: i128 = bounds.get(2).copied().unwrap_or(1);
+        if step == 0 {
+            return self.raise(ErrorKind::ZeroStep(range.operator.clone()));
+        }
+
+        // the range counts in the type both bounds fit, the step only has to be an integer
+        let type_: NumericType = types[0].common(types[1]);
+        for bound in &bounds[..2] {
+            if numeric::from_integer(*bound, type_).is_none() {
+                return self.raise(ErrorKind::OutOfRange(range.operator.clone(), bound.to_string(), type_.name()));
+            }
+        }
+
+        Value::Range(Rc::new(RogueRange::new(bounds[0], bounds[1], step, range.operator.type_ == TokenType::DotDotEqual, type_)))
+    }
+
     fn visit_expr_stmt(&mut self, expr: &crate::parser::ast::Expression) {
         self.evaluate(&*expr.expr);
     }
 
     fn visit_echo_stmt(&mut self, echo: &crate::parser::ast::Echo) {
         let value: Value = self.evaluate(&*echo.expr);
-        println!("{value}");
+        if self.unwind.is_none() {
+            println!("{value}");
+        }
     }
 
     fn visit_block_stmt(&mut self, block: &crate::parser::ast::Block) {
-        self.execute_block(&block.statements, Environment::with_enclosing(self.environment.clone()));
+        self.execute_block(&block.statements, Environment::with_enclosing(Rc::clone(&self.environment)));
     }
 
     fn visit_if_stmt(&mut self, if_: &crate::parser::ast::If) {
         let temp: Value = if_.condition.accept(self);
+        if self.unwind.is_some() {
+            return;
+        }
         if self.is_truthy(temp) {
             if_.then_branch.accept(self);
         } else if if_.else_branch.is_some() {
@@ -167,51 +850,1349 @@ impl Visitor for Interpreter {
     }
 
     fn visit_while_stmt(&mut self, while_: &crate::parser::ast::While) {
-        let value: Value = self.evaluate(&*while_.condition);
-        while self.is_truthy(value.clone()) {
+        loop {
+            // the condition has to be re-evaluated, the body may have changed it
+            let value: Value = self.evaluate(&*while_.condition);
+            if self.unwind.is_some() || !self.is_truthy(value) {
+                break;
+            }
             while_.body.accept(self);
+            if !self.loop_control(&while_.label) {
+                break;
+            }
         }
     }
 
-    fn visit_var_decl(&mut self, var: &crate::parser::ast::Var) {
-        let mut value: Option<Value> = None;
-        if let Some(n) = &var.expr {
-            value = Some(self.evaluate(&**n));
-        }
-
-        // type checking
-        self.match_types(var, value.clone());
+    fn visit_break_stmt(&mut self, break_: &crate::parser::ast::Break) {
+        self.unwind = Some(Unwind::Break(break_.label.as_ref().map(|label| label.lexeme.clone())));
+    }
 
-        self.environment.define(var.name.lexeme.clone(), VarAttrib(var.datatype.clone(), value, var.mutability));
-        println!("{:?}", self.environment.map);
+    fn visit_continue_stmt(&mut self, continue_: &crate::parser::ast::Continue) {
+        self.unwind = Some(Unwind::Continue(continue_.label.as_ref().map(|label| label.lexeme.clone())));
     }
-}
 
-impl Interpreter {
-    fn match_types(&mut self, var: &crate::parser::ast::Var, value: Option<Value>) {
-        if let Some(type_) = &var.datatype {
-            if let TokenType::Identifier = type_.type_ {
-                if let Some(value) = value {
-                    if value.is_i32() {
-                        self.assert_type(type_, &type_.lexeme, &["i32", "u32"]);
+    fn visit_for_stmt(&mut self, for_: &crate::parser::ast::For) {
+        let iterable: Value = self.evaluate(&*for_.iterable);
+        if self.unwind.is_some() {
+            return;
+        }
+
+        match iterable {
+            Value::Range(range) => {
+                for n in range.values() {
+                    if !self.run_iteration(for_, n) {
+                        break;
                     }
-                    if value.is_f64() {
-                        self.assert_type(type_, &type_.lexeme, &["f64", "f32"]);
+                }
+            },
+            Value::Array(array) => {
+                // a snapshot, the body may change the array it walks over
+                let elements: Vec<Value> = array.borrow().clone();
+                for element in elements {
+                    if !self.run_iteration(for_, element) {
+                        break;
                     }
-                    if value.is_boolean() {
-                        self.assert_type(type_, &type_.lexeme, &["boolean"]);
+                }
+            },
+            Value::Tuple(tuple) => {
+                for element in tuple.iter() {
+                    if !self.run_iteration(for_, element.clone()) {
+                        break;
                     }
-                    if value.is_string() {
-                        self.assert_type(type_, &type_.lexeme, &["String", "string"]);
+                }
+            },
+            Value::Map(map) => {
+                let keys: Vec<Value> = map.borrow().keys();
+                for key in keys {
+                    if !self.run_iteration(for_, key) {
+                        break;
                     }
                 }
-            }
-        }
-    }
-
-    fn assert_type(&self, token: &Token, input: &String, expected: &[&str]) {
-        if !expected.contains(&input.as_str()) {
-            self.error_handler.throw(ErrorKind::TypeMismatch(token.clone(), input.clone(), expected.iter().map(|x| x.to_string()).collect()));
-        }
+            },
+            Value::String(string) => {
+                for c in string.chars() {
+                    if !self.run_iteration(for_, Value::String(c.to_string())) {
+                        break;
+                    }
+                }
+            },
+            value => {
+                self.raise(ErrorKind::NotIterable(for_.keyword.clone(), value));
+            },
+        }
+    }
+
+    fn visit_match_stmt(&mut self, match_: &crate::parser::ast::Match) {
+        let subject: Value = self.evaluate(&*match_.subject);
+        if self.unwind.is_some() {
+            return;
+        }
+
+        for arm in &match_.arms {
+            // each arm gets its own scope holding the bound payload values
+            let mut environment: Environment = Environment::with_enclosing(Rc::clone(&self.environment));
+
+            if let crate::parser::ast::Pattern::Variant { enum_name, variant, bindings } = &arm.pattern {
+                let Value::Enum(enum_) = self.evaluate(enum_name) else {
+                    self.raise(ErrorKind::NotAnEnum(enum_name.name.clone()));
+                    return;
+                };
+                let Value::Variant(value) = &subject else {
+                    continue;
+                };
+                if !Rc::ptr_eq(&value.enum_, &enum_) || value.name() != variant.lexeme {
+                    continue;
+                }
+
+                for (binding, payload) in bindings.iter().zip(&value.values) {
+                    if binding.lexeme != "_" {
+                        environment.define(binding.lexeme.clone(), VarAttrib(None, Some(payload.clone()), false));
+                    }
+                }
+            }
+
+            self.execute_block(std::slice::from_ref(&arm.body), environment);
+            return;
+        }
+
+        self.raise(ErrorKind::NoMatchingArm(match_.keyword.clone(), subject));
+    }
+
+    fn visit_throw_stmt(&mut self, throw: &crate::parser::ast::Throw) {
+        let value: Value = self.evaluate(&*throw.value);
+        if self.unwind.is_some() {
+            return;
+        }
+
+        // rethrowing a caught error keeps its original message and position
+        let error: Rc<RogueError> = match value {
+            Value::Error(error) => error,
+            value => Rc::new(RogueError::new(&self.error_handler, ErrorKind::Uncaught(throw.keyword.clone(), value.clone()), Some(value))),
+        };
+        self.unwind = Some(Unwind::Throw(error));
+    }
+
+    fn visit_try_stmt(&mut self, try_: &crate::parser::ast::Try) {
+        self.execute_block(&try_.body, Environment::with_enclosing(Rc::clone(&self.environment)));
+
+        let Some(Unwind::Throw(error)) = self.unwind.take_if(|unwind| matches!(unwind, Unwind::Throw(_))) else {
+            return;
+        };
+
+        let mut environment: Environment = Environment::with_enclosing(Rc::clone(&self.environment));
+        environment.define(try_.name.lexeme.clone(), VarAttrib(None, Some(Value::Error(error)), false));
+        self.execute_block(&try_.handler, environment);
+    }
+
+    fn visit_defer_stmt(&mut self, defer: &crate::parser::ast::Defer) {
+        if let Some(deferred) = self.deferred.last_mut() {
+            deferred.push(Rc::clone(&defer.statement));
+        }
+    }
+
+    fn visit_return_stmt(&mut self, return_: &crate::parser::ast::Return) {
+        let mut value: Value = Value::Null;
+        if let Some(expr) = &return_.value {
+            value = self.evaluate(&**expr);
+        }
+
+        // an error raised while evaluating the value keeps unwinding instead
+        if self.unwind.is_none() {
+            self.unwind = Some(Unwind::Return(value));
+        }
+    }
+
+    fn visit_entity_decl(&mut self, entity: &crate::parser::ast::Entity) {
+        let mut parent: Option<Rc<EntityClass>> = None;
+        if let Some(parent_name) = &entity.parent {
+            let Value::Entity(parent_entity) = self.evaluate(parent_name) else {
+                self.raise(ErrorKind::NotAnEntity(parent_name.name.clone()));
+                return;
+            };
+            if !parent_entity.open {
+                self.raise(ErrorKind::EntityNotOpen(parent_name.name.clone(), parent_entity.name.lexeme.clone()));
+                return;
+            }
+            parent = Some(parent_entity);
+        }
+
+        let mut traits: Vec<Rc<RogueTrait>> = Vec::new();
+        for trait_name in &entity.traits {
+            let Value::Trait(trait_) = self.evaluate(trait_name) else {
+                self.raise(ErrorKind::NotATrait(trait_name.name.clone()));
+                return;
+            };
+            traits.push(trait_);
+        }
+
+        let mut closure: Rc<RefCell<Environment>> = Rc::clone(&self.environment);
+        if let Some(parent) = &parent {
+            let mut environment: Environment = Environment::with_enclosing(closure);
+            environment.define("parent".to_string(), VarAttrib(None, Some(Value::Entity(Rc::clone(parent))), false));
+            closure = Rc::new(RefCell::new(environment));
+        }
+
+        let mut methods: HashMap<String, Rc<RogueFunction>> = HashMap::new();
+        for method in &entity.methods {
+            let is_initializer: bool = method.name.type_ == TokenType::Init;
+
+            if !is_initializer {
+                let inherited: bool = parent.as_ref().and_then(|p| p.find_method(&method.name.lexeme)).is_some();
+                if inherited && !method.overrides {
+                    self.raise(ErrorKind::MissingOverride(method.name.clone()));
+                    return;
+                }
+                if !inherited && method.overrides {
+                    self.raise(ErrorKind::NothingToOverride(method.name.clone()));
+                    return;
+                }
+            }
+
+            let function: RogueFunction = RogueFunction::new(method.name.clone(), method.return_type.clone(), &method.params, Rc::clone(&method.body), Rc::clone(&closure), is_initializer);
+            methods.insert(method.name.lexeme.clone(), Rc::new(function));
+        }
+
+        for trait_ in &traits {
+            for (name, default) in &trait_.defaults {
+                let inherited: bool = parent.as_ref().and_then(|p| p.find_method(name)).is_some();
+                if !methods.contains_key(name) && !inherited {
+                    methods.insert(name.clone(), Rc::clone(default));
+                }
+            }
+        }
+
+        for trait_ in &traits {
+            for (required, arity) in &trait_.required {
+                let provided: Option<Rc<RogueFunction>> = match methods.get(&required.lexeme) {
+                    Some(method) => Some(Rc::clone(method)),
+                    None => parent.as_ref().and_then(|p| p.find_method(&required.lexeme)),
+                };
+
+                let error: Option<ErrorKind> = match provided {
+                    None => Some(ErrorKind::MissingTraitMethod(required.clone(), entity.name.lexeme.clone(), trait_.name.lexeme.clone())),
+                    Some(method) if method.arity() != *arity => {
+                        Some(ErrorKind::TraitSignatureMismatch(entity.name.clone(), required.lexeme.clone(), *arity, method.arity()))
+                    },
+                    Some(_) => None,
+                };
+                if let Some(error) = error {
+                    self.raise(error);
+                    return;
+                }
+            }
+        }
+
+        let class: EntityClass = EntityClass::new(entity.name.clone(), entity.open, parent, Rc::clone(&entity.fields), methods, entity.visibility.clone(), closure);
+        self.environment.borrow_mut().define(entity.name.lexeme.clone(), VarAttrib(None, Some(Value::Entity(Rc::new(class))), false));
+    }
+
+    fn visit_trait_decl(&mut self, trait_: &crate::parser::ast::Trait) {
+        let required: Vec<(Token, usize)> = trait_.required.iter().map(|method| (method.name.clone(), method.params.len())).collect();
+
+        let mut defaults: HashMap<String, Rc<RogueFunction>> = HashMap::new();
+        for method in &trait_.defaults {
+            let function: RogueFunction = RogueFunction::new(method.name.clone(), method.return_type.clone(), &method.params, Rc::clone(&method.body), Rc::clone(&self.environment), false);
+            defaults.insert(method.name.lexeme.clone(), Rc::new(function));
+        }
+
+        let value: RogueTrait = RogueTrait::new(trait_.name.clone(), required, defaults);
+        self.environment.borrow_mut().define(trait_.name.lexeme.clone(), VarAttrib(None, Some(Value::Trait(Rc::new(value))), false));
+    }
+
+    fn visit_export_stmt(&mut self, export: &crate::parser::ast::Export) {
+        // nothing imports modules yet, a `pub` declaration runs like any other
+        export.declaration.accept(self);
+    }
+
+    fn visit_enum_decl(&mut self, enum_: &crate::parser::ast::Enum) {
+        let value: RogueEnum = RogueEnum::new(enum_.name.clone(), Rc::clone(&enum_.variants));
+        self.environment.borrow_mut().define(enum_.name.lexeme.clone(), VarAttrib(None, Some(Value::Enum(Rc::new(value))), false));
+    }
+
+    fn visit_function_decl(&mut self, function: &crate::parser::ast::Function) {
+        let callable: RogueFunction = RogueFunction::new(function.name.clone(), function.return_type.clone(), &function.params, Rc::clone(&function.body), Rc::clone(&self.environment), false);
+        self.environment.borrow_mut().define(function.name.lexeme.clone(), VarAttrib(None, Some(Value::Callable(Rc::new(callable))), false));
+    }
+
+    fn visit_var_decl(&mut self, var: &crate::parser::ast::Var) {
+        let mut value: Option<Value> = None;
+        if let Some(n) = &var.expr {
+            value = Some(self.evaluate(&**n));
+            if self.unwind.is_some() {
+                return;
+            }
+        }
+
+        // type checking
+        if !self.match_types(var, &mut value) {
+            return;
+        }
+
+        self.environment.borrow_mut().define(var.name.lexeme.clone(), VarAttrib(var.datatype.clone(), value, var.mutability));
+    }
+
+    fn visit_destructure_decl(&mut self, destructure: &crate::parser::ast::Destructure) {
+        let value: Value = self.evaluate(&*destructure.expr);
+        if self.unwind.is_some() {
+            return;
+        }
+
+        let environment: Rc<RefCell<Environment>> = Rc::clone(&self.environment);
+        self.bind_pattern(&mut environment.borrow_mut(), &destructure.pattern, value);
+    }
+}
+
+impl Interpreter {
+    /// Defines every name in `pattern` in `environment`, in the same order the resolver handed out slots.
+    /// Declarations, loop variables and parameters all bind through here.
+    /// Returns false once a shape or type mismatch was raised.
+    fn bind_pattern(&mut self, environment: &mut Environment, pattern: &VarPattern, mut value: Value) -> bool {
+        let (token, patterns, elements, kind) = match (pattern, &value) {
+            (VarPattern::Name { name, datatype, mutable }, _) => {
+                if let Some(datatype) = datatype {
+                    if !self.check_type(datatype, &mut value) {
+                        return false;
+                    }
+                }
+                environment.define(name.lexeme.clone(), VarAttrib(datatype.clone(), Some(value), *mutable));
+                return true;
+            },
+            // rest patterns are only bound by the tuple or array pattern around them
+            (VarPattern::Rest(pattern), _) => return self.bind_pattern(environment, pattern, value),
+            (VarPattern::Tuple { paren, elements: patterns }, Value::Tuple(tuple)) => (paren, patterns, tuple.to_vec(), "tuple"),
+            (VarPattern::Array { bracket, elements: patterns }, Value::Array(array)) => (bracket, patterns, array.borrow().clone(), "array"),
+            (VarPattern::Tuple { paren, .. }, _) => {
+                self.raise(ErrorKind::DestructureMismatch(paren.clone(), "a tuple".to_string(), value));
+                return false;
+            },
+            (VarPattern::Array { bracket, .. }, _) => {
+                self.raise(ErrorKind::DestructureMismatch(bracket.clone(), "an array".to_string(), value));
+                return false;
+            },
+        };
+
+        let rest: Option<usize> = patterns.iter().position(|pattern| matches!(pattern, VarPattern::Rest(_)));
+        let fixed: usize = patterns.len() - rest.map_or(0, |_| 1);
+        let fits: bool = match rest {
+            Some(_) => elements.len() >= fixed,
+            None => elements.len() == fixed,
+        };
+        if !fits {
+            let at_least: &str = if rest.is_some() { "at least " } else { "" };
+            self.raise(ErrorKind::DestructureMismatch(token.clone(), format!("{kind} of {at_least}{fixed} elements"), value));
+            return false;
+        }
+
+        let Some(rest) = rest else {
+            return patterns.iter().zip(elements).all(|(pattern, element)| self.bind_pattern(environment, pattern, element));
+        };
+
+        // the elements before the rest bind from the front, the ones after it from the back
+        let tail: usize = elements.len() - (patterns.len() - rest - 1);
+        let leftover: Vec<Value> = elements[rest..tail].to_vec();
+        let leftover: Value = match value {
+            Value::Tuple(_) => Value::Tuple(Rc::new(leftover)),
+            _ => Value::Array(Rc::new(RefCell::new(leftover))),
+        };
+
+        patterns[..rest].iter().zip(elements[..rest].iter())
+            .chain(std::iter::once((&patterns[rest], &leftover)))
+            .chain(patterns[rest + 1..].iter().zip(elements[tail..].iter()))
+            .all(|(pattern, element)| self.bind_pattern(environment, pattern, element.clone()))
+    }
+
+    fn match_types(&mut self, var: &crate::parser::ast::Var, value: &mut Option<Value>) -> bool {
+        if let Some(type_) = &var.datatype {
+            if let Some(value) = value {
+                return self.check_type(type_, value);
+            }
+        }
+        true
+    }
+
+    /// Converts `value` to the width the annotation `type_` asks for.
+    /// Raises an error and returns false if it doesn't fit.
+    fn check_type(&mut self, type_: &Token, value: &mut Value) -> bool {
+        match fit_type(type_, value) {
+            Ok(fitted) => {
+                *value = fitted;
+                true
+            },
+            Err(error) => {
+                self.raise(error);
+                false
+            },
+        }
+    }
+}
+
+/// `value` as the annotation `type_` stores it: numbers convert to the annotated width if they
+/// fit, other values only have to be of the annotated kind.
+pub fn fit_type(type_: &Token, value: &Value) -> Result<Value, ErrorKind> {
+    if type_.type_ != TokenType::Identifier {
+        return Ok(value.clone());
+    }
+
+    let expected: Vec<&str> = match (value, NumericType::of(value)) {
+        (Value::Boolean(_), _) => vec!["boolean"],
+        (Value::String(_), _) => vec!["String", "string"],
+        (_, Some(numeric)) => {
+            // integers and floats only convert within their own kind
+            let kind: &[NumericType] = if numeric.is_float() { &NumericType::FLOATS } else { &NumericType::INTEGERS };
+            if let Some(target) = NumericType::from_name(&type_.lexeme).filter(|target| kind.contains(target)) {
+                return numeric::convert(value, target).ok_or_else(|| ErrorKind::OutOfRange(type_.clone(), value.to_string(), target.name()));
+            }
+            kind.iter().map(|type_| type_.name()).collect()
+        },
+        _ => return Ok(value.clone()),
+    };
+
+    match expected.contains(&type_.lexeme.as_str()) {
+        true => Ok(value.clone()),
+        false => Err(ErrorKind::TypeMismatch(type_.clone(), type_.lexeme.clone(), expected.iter().map(|x| x.to_string()).collect())),
+    }
+}
+
+/// An unsuffixed number literal, negated or not.
+fn untyped(expr: &dyn Expr) -> bool {
+    if let Some(unary) = expr.as_any().downcast_ref::<crate::parser::ast::Unary>() {
+        return unary.operator.type_ == TokenType::Minus && untyped(&*unary.right);
+    }
+    expr.as_any().downcast_ref::<crate::parser::ast::Literal>().is_some_and(|literal| literal.untyped)
+}
+
+/// Where `index` points into a sequence of `len` elements, negative indices count from the end.
+fn element_position(index: i128, len: usize) -> Option<usize> {
+    let position: i128 = if index < 0 { len as i128 + index } else { index };
+    if position < 0 || position >= len as i128 {
+        return None;
     }
+    Some(position as usize)
+}
+
+#[cfg(test)]
+fn run_source(source: &str) -> Interpreter {
+    use crate::{lexer::Lexer, parser::Parser, resolver::Resolver};
+
+    let tokens: Vec<Token> = Lexer::new(source.to_string()).tokenize();
+    let stmts: Vec<Box<dyn Stmt>> = Parser::new(tokens).parse();
+    Resolver::new().resolve(&stmts);
+
+    let mut interpreter: Interpreter = Interpreter::new();
+    interpreter.interpret(stmts);
+    interpreter
+}
+
+#[cfg(test)]
+fn global_value(interpreter: &Interpreter, name: &str) -> String {
+    let token: Token = Token::new(TokenType::Identifier, name.to_string(), None, 0, 0);
+    interpreter.globals.borrow().get(token).unwrap().1.unwrap().to_string()
+}
+
+#[test]
+fn test_functions() {
+    let interpreter: Interpreter = run_source("
+        set fib(n) {
+            if (n < 2) return n;
+            return fib(n - 1) + fib(n - 2);
+        }
+        void nothing() { return; }
+        set result = fib(10);
+        set empty = nothing();
+    ");
+
+    assert_eq!(global_value(&interpreter, "result"), "55");
+    assert_eq!(global_value(&interpreter, "empty"), "null");
+}
+
+#[test]
+fn test_closures() {
+    let interpreter: Interpreter = run_source("
+        set make_counter() {
+            set mut count = 0;
+            set increment() {
+                count = count + 1;
+                return count;
+            }
+            return increment;
+        }
+        set counter = make_counter();
+        counter();
+        counter();
+        set third = counter();
+        set other = make_counter()();
+    ");
+
+    assert_eq!(global_value(&interpreter, "third"), "3");
+    assert_eq!(global_value(&interpreter, "other"), "1");
+}
+
+#[test]
+fn test_block_assignments_persist() {
+    let interpreter: Interpreter = run_source("
+        set mut x = 1;
+        {
+            x = 2;
+            {
+                x = x + 1;
+            }
+        }
+        set mut shadowed = 1;
+        {
+            set mut shadowed = 5;
+            shadowed = 6;
+        }
+        set mut i = 0;
+        set mut total = 0;
+        while (i < 4) {
+            total = total + i;
+            i = i + 1;
+        }
+    ");
+
+    assert_eq!(global_value(&interpreter, "x"), "3");
+    assert_eq!(global_value(&interpreter, "shadowed"), "1");
+    assert_eq!(global_value(&interpreter, "i"), "4");
+    assert_eq!(global_value(&interpreter, "total"), "6");
+}
+
+#[test]
+fn test_function_assigns_outer_variable() {
+    let interpreter: Interpreter = run_source("
+        set mut calls = 0;
+        void track() {
+            calls = calls + 1;
+        }
+        track();
+        track();
+    ");
+
+    assert_eq!(global_value(&interpreter, "calls"), "2");
+}
+
+#[test]
+fn test_resolved_slots() {
+    let interpreter: Interpreter = run_source("
+        set a = 1;
+        set mut result = 0;
+        set f(x) {
+            set y = x + 10;
+            {
+                set x = 100;
+                set z = y;
+                result = x + z;
+            }
+        }
+        f(a);
+    ");
+
+    assert_eq!(global_value(&interpreter, "result"), "111");
+}
+
+#[test]
+fn test_entities() {
+    let interpreter: Interpreter = run_source("
+        entity Point {
+            set x = 0;
+            i32 mut y = 0;
+
+            init(x, y) {
+                this.x = x;
+                this.y = y;
+            }
+
+            set sum() {
+                return this.x + this.y;
+            }
+        }
+        set p = new Point(1, 2);
+        p.y = 5;
+        set total = p.sum();
+        set method = p.sum;
+        set bound = method();
+    ");
+
+    assert_eq!(global_value(&interpreter, "total"), "6");
+    assert_eq!(global_value(&interpreter, "bound"), "6");
+    assert_eq!(global_value(&interpreter, "p"), "<Point instance>");
+}
+
+#[test]
+#[should_panic(expected = "Cannot assign to immutable data")]
+fn test_immutable_field_outside_init() {
+    run_source("entity Point { set x = 0; init(x) { this.x = x; } } set p = new Point(1);
+        p.x = 2;");
+}
+
+#[test]
+fn test_immutable_field_of_other_instance() {
+    let interpreter: Interpreter = run_source("
+        entity Point {
+            set x = 0;
+            init(other) {
+                if (other != 0) other.x = 9;
+            }
+        }
+        entity Inner { init(outer) { outer.tag = \"changed\"; } }
+        entity Outer {
+            set tag = \"fixed\";
+            init() { new Inner(this); }
+        }
+        set a = new Point(0);
+        set mut sibling = 0;
+        try { new Point(a); } catch (e) { sibling = e.line; }
+        set mut nested = 0;
+        try { new Outer(); } catch (e) { nested = e.line; }
+    ");
+
+    assert_eq!(global_value(&interpreter, "sibling"), "5");
+    assert_eq!(global_value(&interpreter, "nested"), "8");
+}
+
+#[test]
+fn test_inheritance() {
+    let interpreter: Interpreter = run_source("
+        open entity Animal {
+            set name = \"animal\";
+            init(name) { this.name = name; }
+            set speak() { return \"...\"; }
+            set describe() { return this.speak(); }
+        }
+        entity Dog : Animal {
+            override set speak() { return \"woof\"; }
+            set quiet() { return parent.speak(); }
+        }
+        set dog = new Dog(\"rex\");
+        set name = dog.name;
+        set loud = dog.describe();
+        set quiet = dog.quiet();
+    ");
+
+    assert_eq!(global_value(&interpreter, "name"), "rex");
+    assert_eq!(global_value(&interpreter, "loud"), "woof");
+    assert_eq!(global_value(&interpreter, "quiet"), "...");
+}
+
+#[test]
+#[should_panic(expected = "Cannot inherit from closed entity")]
+fn test_inherit_from_closed_entity() {
+    run_source("entity A { }
+        entity B : A { }");
+}
+
+#[test]
+#[should_panic(expected = "Missing `override`")]
+fn test_missing_override() {
+    run_source("open entity A { set f() { return 1; } }
+        entity B : A { set f() { return 2; } }");
+}
+
+#[test]
+fn test_traits() {
+    let interpreter: Interpreter = run_source("
+        trait Shape {
+            set area();
+            set describe() { return this.area() * 2; }
+        }
+        entity Square with Shape {
+            set side = 3;
+            set area() { return this.side * this.side; }
+        }
+        set square = new Square();
+        set area = square.area();
+        set described = square.describe();
+    ");
+
+    assert_eq!(global_value(&interpreter, "area"), "9");
+    assert_eq!(global_value(&interpreter, "described"), "18");
+}
+
+#[test]
+#[should_panic(expected = "Missing trait method")]
+fn test_missing_trait_method() {
+    run_source("trait Shape { set area(); }
+        entity Square with Shape { }");
+}
+
+#[test]
+fn test_visibility() {
+    let interpreter: Interpreter = run_source("
+        open entity Account {
+            priv set mut balance = 0;
+            prot set owner = \"bob\";
+            set deposit(n) { this.balance = this.balance + n; return this.balance; }
+        }
+        entity Savings : Account {
+            set who() { return this.owner; }
+        }
+        pub set account = new Savings();
+        set balance = account.deposit(10);
+        set owner = account.who();
+    ");
+
+    assert_eq!(global_value(&interpreter, "balance"), "10");
+    assert_eq!(global_value(&interpreter, "owner"), "bob");
+}
+
+#[test]
+#[should_panic(expected = "Inaccessible member")]
+fn test_private_member_outside_entity() {
+    run_source("entity Account { priv set balance = 0; }
+        set balance = new Account().balance;");
+}
+
+#[test]
+fn test_enums() {
+    let interpreter: Interpreter = run_source("
+        enum Shape { Circle(i32 r), Rect(w, h), Empty }
+        set area(s) {
+            match (s) {
+                Shape.Circle(r) -> return 3 * r * r;
+                Shape.Rect(w, h) -> return w * h;
+                Shape.Empty -> return 0;
+            }
+        }
+        set circle = area(Shape.Circle(2));
+        set rect = area(Shape.Rect(2, 5));
+        set empty = area(Shape.Empty);
+        set shape = Shape.Rect(1, 2);
+        set width = shape.w;
+        set mut other = 0;
+        match (Shape.Empty) {
+            Shape.Circle(_) -> other = 1;
+            _ -> other = 2;
+        }
+    ");
+
+    assert_eq!(global_value(&interpreter, "circle"), "12");
+    assert_eq!(global_value(&interpreter, "rect"), "10");
+    assert_eq!(global_value(&interpreter, "empty"), "0");
+    assert_eq!(global_value(&interpreter, "shape"), "Shape.Rect(1, 2)");
+    assert_eq!(global_value(&interpreter, "width"), "1");
+    assert_eq!(global_value(&interpreter, "other"), "2");
+}
+
+#[test]
+#[should_panic(expected = "Non-exhaustive match")]
+fn test_non_exhaustive_match() {
+    run_source("enum Light { Red, Green }
+        match (Light.Red) { Light.Red -> echo 1; }");
+}
+
+#[test]
+fn test_try_catch() {
+    let interpreter: Interpreter = run_source("
+        set fail(msg) { throw msg; }
+        set mut log = 0;
+        try {
+            fail(\"boom\");
+            log = 1;
+        } catch (e) {
+            set message = e.message;
+            set line = e.line;
+        }
+        set mut thrown = 0;
+        try { throw 42; } catch (e) { thrown = e.value; }
+        set frozen = 1;
+        set mut runtime = 0;
+        try {
+            try { frozen = 2; } catch (e) { throw e; }
+        } catch (e) {
+            runtime = e.line;
+        }
+    ");
+
+    assert_eq!(global_value(&interpreter, "log"), "0");
+    assert_eq!(global_value(&interpreter, "thrown"), "42");
+    assert_eq!(global_value(&interpreter, "frozen"), "1");
+    assert_eq!(global_value(&interpreter, "runtime"), "16");
+}
+
+#[test]
+#[should_panic(expected = "Uncaught error")]
+fn test_uncaught_throw() {
+    run_source("set f() { throw \"boom\"; }
+        f();");
+}
+
+#[test]
+fn test_uninitialized_variable() {
+    let interpreter: Interpreter = run_source("
+        set mut x;
+        set mut caught = 0;
+        try { echo x; } catch (e) { caught = e.line; }
+        x = 1;
+        set read = x;
+    ");
+
+    assert_eq!(global_value(&interpreter, "caught"), "4");
+    assert_eq!(global_value(&interpreter, "read"), "1");
+}
+
+#[test]
+#[should_panic(expected = "Uninitialized variable")]
+fn test_uncaught_uninitialized_variable() {
+    run_source("set f() { set mut y; return y; }
+        f();");
+}
+
+#[test]
+fn test_defer() {
+    let interpreter: Interpreter = run_source("
+        set mut log = 0;
+        set mut handles = 1;
+        set work() {
+            defer log = log * 10 + 1;
+            defer log = log * 10 + 2;
+            return log;
+        }
+        set before = work();
+        set order = log;
+        set fail() {
+            handles = 0;
+            defer handles = 1;
+            throw \"disk full\";
+        }
+        try { fail(); } catch (e) {}
+        {
+            defer log = 0;
+            log = 5;
+        }
+    ");
+
+    assert_eq!(global_value(&interpreter, "before"), "0");
+    assert_eq!(global_value(&interpreter, "order"), "21");
+    assert_eq!(global_value(&interpreter, "handles"), "1");
+    assert_eq!(global_value(&interpreter, "log"), "0");
+}
+
+#[test]
+fn test_for_loops() {
+    let interpreter: Interpreter = run_source("
+        set mut sum = 0;
+        for (i in 0..5) sum = sum + i;
+        set mut inclusive = 0;
+        for (i in 1..=4) inclusive = inclusive + i;
+        set mut countdown = 0;
+        for (i in 10..0 step -3) countdown = countdown * 100 + i;
+        set mut times = 0;
+        for (0..3) times = times + 1;
+        set mut letters = 0;
+        for (c in \"rogue\") { letters = letters + 1; }
+        set mut last = 0;
+        for (i in 0..3) {
+            set capture() { return i; }
+            last = capture;
+        }
+        set captured = last();
+        set range = 0..10 step 2;
+    ");
+
+    assert_eq!(global_value(&interpreter, "sum"), "10");
+    assert_eq!(global_value(&interpreter, "inclusive"), "10");
+    assert_eq!(global_value(&interpreter, "countdown"), "10070401");
+    assert_eq!(global_value(&interpreter, "times"), "3");
+    assert_eq!(global_value(&interpreter, "letters"), "5");
+    assert_eq!(global_value(&interpreter, "captured"), "2");
+    assert_eq!(global_value(&interpreter, "range"), "0..10 step 2");
+}
+
+#[test]
+#[should_panic(expected = "Range step of zero")]
+fn test_zero_step() {
+    run_source("for (i in 0..3 step 0) echo i;");
+}
+
+#[test]
+fn test_break_continue() {
+    let interpreter: Interpreter = run_source("
+        set mut i = 0;
+        set mut counted = 0;
+        while (true) {
+            i = i + 1;
+            if (i > 9) break;
+            if (i * 2 == 8) continue;
+            counted = counted + 1;
+        }
+        set mut pairs = 0;
+        outer: for (a in 0..5) {
+            for (b in 0..5) {
+                if (b > a) continue outer;
+                if (a == 3) break outer;
+                pairs = pairs + 1;
+            }
+        }
+        set mut cleaned = 0;
+        for (n in 0..3) {
+            defer cleaned = cleaned + 1;
+            break;
+        }
+    ");
+
+    assert_eq!(global_value(&interpreter, "i"), "10");
+    assert_eq!(global_value(&interpreter, "counted"), "8");
+    assert_eq!(global_value(&interpreter, "pairs"), "6");
+    assert_eq!(global_value(&interpreter, "cleaned"), "1");
+}
+
+#[test]
+fn test_elif() {
+    let interpreter: Interpreter = run_source("
+        set classify(n) {
+            if (n < 0) return \"negative\";
+            elif (n == 0) return \"zero\";
+            elif (n < 10) { return \"small\"; }
+            else return \"large\";
+        }
+        set negative = classify(-1);
+        set zero = classify(0);
+        set small = classify(5);
+        set large = classify(50);
+    ");
+
+    assert_eq!(global_value(&interpreter, "negative"), "negative");
+    assert_eq!(global_value(&interpreter, "zero"), "zero");
+    assert_eq!(global_value(&interpreter, "small"), "small");
+    assert_eq!(global_value(&interpreter, "large"), "large");
+}
+
+#[test]
+fn test_arrays() {
+    let interpreter: Interpreter = run_source("
+        set xs = [1, 2, 3, 4, 5];
+        set alias = xs;
+        alias[0] = 10;
+        xs[-1] = 50;
+        set first = xs[0];
+        set last = xs[-1];
+        set middle = xs[1..3];
+        set tail = xs[-2..5];
+        set reversed = xs[4..=0 step -1];
+        set mut sum = 0;
+        for (x in xs) sum = sum + x;
+        set nested = [[1], []];
+        set mut caught = 0;
+        try { echo xs[5]; } catch (e) { caught = e.line; }
+    ");
+
+    assert_eq!(global_value(&interpreter, "first"), "10");
+    assert_eq!(global_value(&interpreter, "last"), "50");
+    assert_eq!(global_value(&interpreter, "middle"), "[2, 3]");
+    assert_eq!(global_value(&interpreter, "tail"), "[4, 50]");
+    assert_eq!(global_value(&interpreter, "reversed"), "[50, 4, 3, 2, 10]");
+    assert_eq!(global_value(&interpreter, "sum"), "69");
+    assert_eq!(global_value(&interpreter, "nested"), "[[1], []]");
+    assert_eq!(global_value(&interpreter, "caught"), "15");
+}
+
+#[test]
+#[should_panic(expected = "Index out of bounds")]
+fn test_index_out_of_bounds() {
+    run_source("set xs = [1, 2];
+        echo xs[-3];");
+}
+
+#[test]
+fn test_maps() {
+    let interpreter: Interpreter = run_source("
+        set ages = { \"bob\": 31, \"alice\": 28, };
+        ages[\"carol\"] = 40;
+        ages[\"bob\"] = 32;
+        set mixed = { 1: \"one\", true: [1, 2], \"nested\": {} };
+        set bob = ages[\"bob\"];
+        set has_alice = \"alice\" in ages;
+        set has_dave = \"dave\" in ages;
+        set in_array = 2 in [1, 2, 3];
+        set in_string = \"og\" in \"rogue\";
+        set mut order = \"\";
+        for (name in ages) order = name;
+        set mut missing = 0;
+        try { echo ages[\"dave\"]; } catch (e) { missing = e.line; }
+        set mut invalid = \"\";
+        try { set floats = { 1.0: 2 }; } catch (e) { invalid = e.message; }
+    ");
+
+    assert_eq!(global_value(&interpreter, "ages"), "{bob: 32, alice: 28, carol: 40}");
+    assert_eq!(global_value(&interpreter, "mixed"), "{1: one, true: [1, 2], nested: {}}");
+    assert_eq!(global_value(&interpreter, "bob"), "32");
+    assert_eq!(global_value(&interpreter, "has_alice"), "true");
+    assert_eq!(global_value(&interpreter, "has_dave"), "false");
+    assert_eq!(global_value(&interpreter, "in_array"), "true");
+    assert_eq!(global_value(&interpreter, "in_string"), "true");
+    assert_eq!(global_value(&interpreter, "order"), "carol");
+    assert_eq!(global_value(&interpreter, "missing"), "14");
+    assert_eq!(global_value(&interpreter, "invalid"), "`1` (f64) cannot be used as a map key.");
+}
+
+#[test]
+fn test_destructuring() {
+    let interpreter: Interpreter = run_source("
+        set pair = (1, \"one\");
+        set single = (2,);
+        set grouped = (2);
+        set (x, mut y) = pair;
+        y = \"uno\";
+        set [head, ..rest] = [1, 2, 3, 4];
+        set [first, ..middle, last] = [1, 2, 3, 4];
+        set (a, (b, c), ..others) = (1, (2, 3));
+        set mut sum = 0;
+        for (n in (1, 2, 3)) sum = sum + n;
+        set second = pair[1];
+        set mut mismatch = 0;
+        try { set (p, q) = [1, 2]; } catch (e) { mismatch = e.line; }
+        try { set [p, q] = [1, 2, 3]; } catch (e) { mismatch = mismatch + e.line; }
+    ");
+
+    assert_eq!(global_value(&interpreter, "pair"), "(1, one)");
+    assert_eq!(global_value(&interpreter, "single"), "(2,)");
+    assert_eq!(global_value(&interpreter, "grouped"), "2");
+    assert_eq!(global_value(&interpreter, "x"), "1");
+    assert_eq!(global_value(&interpreter, "y"), "uno");
+    assert_eq!(global_value(&interpreter, "head"), "1");
+    assert_eq!(global_value(&interpreter, "rest"), "[2, 3, 4]");
+    assert_eq!(global_value(&interpreter, "middle"), "[2, 3]");
+    assert_eq!(global_value(&interpreter, "last"), "4");
+    assert_eq!(global_value(&interpreter, "c"), "3");
+    assert_eq!(global_value(&interpreter, "others"), "()");
+    assert_eq!(global_value(&interpreter, "sum"), "6");
+    assert_eq!(global_value(&interpreter, "second"), "one");
+    assert_eq!(global_value(&interpreter, "mismatch"), "29");
+}
+
+#[test]
+#[should_panic(expected = "Pattern does not match")]
+fn test_destructure_mismatch() {
+    run_source("set (a, b) = (1, 2, 3);");
+}
+
+#[test]
+fn test_loop_and_parameter_patterns() {
+    let interpreter: Interpreter = run_source("
+        set mut names = \"\";
+        set mut total = 0;
+        for ((name, score) in [(\"a\", 1), (\"b\", 2)]) {
+            names = names + name;
+            total = total + score;
+        }
+        set mut heads = 0;
+        for ([head, ..tail] in [[1, 2], [3]]) heads = heads + head;
+        set mut doubled = 0;
+        for (mut i in 0..3) {
+            i = i * 2;
+            doubled = doubled + i;
+        }
+        set mut mismatch = 0;
+        try { for ((a, b) in [1, 2]) echo a; } catch (e) { mismatch = e.line; }
+        set narrow(i8 mut n) {
+            n = n + 1;
+            return n;
+        }
+        set fitted = narrow(1);
+        set mut overflow = 0;
+        try { narrow(127); } catch (e) { overflow = e.line; }
+        set mut rejected = 0;
+        try { narrow(300); } catch (e) { rejected = e.line; }
+    ");
+
+    assert_eq!(global_value(&interpreter, "names"), "ab");
+    assert_eq!(global_value(&interpreter, "total"), "3");
+    assert_eq!(global_value(&interpreter, "heads"), "4");
+    assert_eq!(global_value(&interpreter, "doubled"), "6");
+    assert_eq!(global_value(&interpreter, "mismatch"), "16");
+    assert_eq!(global_value(&interpreter, "fitted"), "2");
+    assert_eq!(global_value(&interpreter, "overflow"), "18");
+    assert_eq!(global_value(&interpreter, "rejected"), "17");
+}
+
+#[test]
+fn test_interpolation() {
+    let interpreter: Interpreter = run_source(r#"
+        set x = 2;
+        set name = "rogue";
+        set simple = "x = {x}";
+        set many = "{name}: {x} of {[1, 2]} and { {"a": 1}["a"] }";
+        set nested = "outer {"inner {name}"}!";
+    "#);
+
+    assert_eq!(global_value(&interpreter, "simple"), "x = 2");
+    assert_eq!(global_value(&interpreter, "many"), "rogue: 2 of [1, 2] and 1");
+    assert_eq!(global_value(&interpreter, "nested"), "outer inner rogue!");
 }
+
+#[test]
+fn test_integer_arithmetic() {
+    let interpreter: Interpreter = run_source("
+        set sum = 1 + 2;
+        set truncated = -7 / 2;
+        set floored = -7 // 2;
+        set remainder = -7 % 3;
+        set mixed = 7 / 2.0;
+        set float_floor = 7.5 // 2;
+        set compared = 3 < 3.5;
+        set mut overflow = 0;
+        try { echo 2147483647 + 1; } catch (e) { overflow = e.line; }
+        set mut by_zero = 0;
+        try { echo 1 % 0; } catch (e) { by_zero = e.line; }
+    ");
+
+    assert_eq!(global_value(&interpreter, "sum"), "3");
+    assert!(matches!(interpreter.globals.borrow().get(Token::new(TokenType::Identifier, "sum".to_string(), None, 0, 0)).unwrap().1, Some(Value::Integer(3))));
+    assert_eq!(global_value(&interpreter, "truncated"), "-3");
+    assert_eq!(global_value(&interpreter, "floored"), "-4");
+    assert_eq!(global_value(&interpreter, "remainder"), "-1");
+    assert_eq!(global_value(&interpreter, "mixed"), "3.5");
+    assert_eq!(global_value(&interpreter, "float_floor"), "3");
+    assert_eq!(global_value(&interpreter, "compared"), "true");
+    assert_eq!(global_value(&interpreter, "overflow"), "10");
+    assert_eq!(global_value(&interpreter, "by_zero"), "12");
+}
+
+#[test]
+#[should_panic(expected = "Division by zero")]
+fn test_division_by_zero() {
+    run_source("echo 10 / (5 - 5);");
+}
+
+#[test]
+fn test_string_operators() {
+    let interpreter: Interpreter = run_source("
+        enum Shape { Circle(r), Empty }
+        set joined = \"ro\" + \"gue\";
+        set repeated = \"ab\" * 3;
+        set before = \"apple\" < \"banana\";
+        set after = \"b\" >= \"ba\";
+        set same = \"a\" == \"a\";
+        set numbers = 1 == 1.0;
+        set mixed = \"1\" == 1;
+        set nothing() {}
+        set nulls = nothing() == nothing();
+        set null_false = nothing() == false;
+        set null_zero = nothing() != 0;
+        set ranges = (0..3) == (0..3);
+        set variants = Shape.Circle(1) == Shape.Circle(1);
+        set payloads = Shape.Circle(1) != Shape.Circle(2);
+        set xs = [1];
+        set identity = xs == xs;
+        set copies = [1] == [1];
+        set widths = (1, 2u8) == (1.0, 2);
+        set lengths = (1, 2) == (1, 2, 3);
+        set tuple_array = (1, 2) == [1, 2];
+        set variant_tuple = Shape.Empty != (1,);
+        set array_map = [] == {};
+        set mut failed = 0;
+        try { echo \"a\" + 1; } catch (e) { failed = e.line; }
+    ");
+
+    assert_eq!(global_value(&interpreter, "joined"), "rogue");
+    assert_eq!(global_value(&interpreter, "repeated"), "ababab");
+    assert_eq!(global_value(&interpreter, "before"), "true");
+    assert_eq!(global_value(&interpreter, "after"), "false");
+    assert_eq!(global_value(&interpreter, "same"), "true");
+    assert_eq!(global_value(&interpreter, "numbers"), "true");
+    assert_eq!(global_value(&interpreter, "mixed"), "false");
+    assert_eq!(global_value(&interpreter, "nulls"), "true");
+    assert_eq!(global_value(&interpreter, "null_false"), "false");
+    assert_eq!(global_value(&interpreter, "null_zero"), "true");
+    assert_eq!(global_value(&interpreter, "ranges"), "true");
+    assert_eq!(global_value(&interpreter, "variants"), "true");
+    assert_eq!(global_value(&interpreter, "payloads"), "true");
+    assert_eq!(global_value(&interpreter, "identity"), "true");
+    assert_eq!(global_value(&interpreter, "copies"), "false");
+    assert_eq!(global_value(&interpreter, "widths"), "true");
+    assert_eq!(global_value(&interpreter, "lengths"), "false");
+    assert_eq!(global_value(&interpreter, "tuple_array"), "false");
+    assert_eq!(global_value(&interpreter, "variant_tuple"), "true");
+    assert_eq!(global_value(&interpreter, "array_map"), "false");
+    assert_eq!(global_value(&interpreter, "failed"), "26");
+}
+
+#[test]
+fn test_numeric_widths() {
+    let interpreter: Interpreter = run_source("
+        u8 mut small = 200;
+        i64 big = 3000000000;
+        set suffixed = 10u8 + 5u8;
+        set adopted = 10u8 + 5;
+        set negated = 10i8 * -3;
+        set mut literal_overflow = 0;
+        try { echo 1u8 + 255; } catch (e) { literal_overflow = e.message; }
+        set mut literal_range = 0;
+        try { echo 300 - 29u8; } catch (e) { literal_range = e.message; }
+        set promoted = 10u8 + 1000i16;
+        set signed = 10u32 - 20i32;
+        set half = 2.5f32 * 2f32;
+        set wide = 2.5f32 + 1.0;
+        set literal = 5000000000;
+        set equal = 10u8 == 10i64;
+        i16 take(i16 n) { return n * 2; }
+        set taken = take(300);
+        set mut overflow = 0;
+        try { echo 200u8 + 100u8; } catch (e) { overflow = e.line; }
+        set mut narrowed = 0;
+        try { small = small + 100; } catch (e) { narrowed = e.line; }
+    ");
+
+    let value = |name: &str| interpreter.globals.borrow().get(Token::new(TokenType::Identifier, name.to_string(), None, 0, 0)).unwrap().1.unwrap();
+    assert!(matches!(value("small"), Value::U8(200)));
+    assert!(matches!(value("big"), Value::I64(3000000000)));
+    assert!(matches!(value("suffixed"), Value::U8(15)));
+    assert!(matches!(value("adopted"), Value::U8(15)));
+    assert!(matches!(value("negated"), Value::I8(-30)));
+    assert!(matches!(value("promoted"), Value::I16(1010)));
+    assert!(matches!(value("signed"), Value::I64(-10)));
+    assert!(matches!(value("half"), Value::F32(x) if x == 5.0));
+    assert!(matches!(value("wide"), Value::F32(x) if x == 3.5));
+    assert!(matches!(value("literal"), Value::I64(5000000000)));
+    assert!(matches!(value("equal"), Value::Boolean(true)));
+    assert!(matches!(value("taken"), Value::I16(600)));
+    assert_eq!(global_value(&interpreter, "literal_overflow"), "`1 + 255` does not fit in `u8`.");
+    assert_eq!(global_value(&interpreter, "literal_range"), "`300` does not fit in `u8`.");
+    assert_eq!(global_value(&interpreter, "overflow"), "20");
+    assert_eq!(global_value(&interpreter, "narrowed"), "22");
+}
+
+#[test]
+fn test_signed_minimum_literals() {
+    let interpreter: Interpreter = run_source("
+        set tiny = -128i8;
+        set short = -32768i16;
+        set int = -2147483648i32;
+        set long = -9223372036854775808i64;
+        set grouped = (-128i8, -128i8);
+        set difference = 100i16 -128i16;
+    ");
+
+    let value = |name: &str| interpreter.globals.borrow().get(Token::new(TokenType::Identifier, name.to_string(), None, 0, 0)).unwrap().1.unwrap();
+    assert!(matches!(value("tiny"), Value::I8(i8::MIN)));
+    assert!(matches!(value("short"), Value::I16(i16::MIN)));
+    assert!(matches!(value("int"), Value::Integer(i32::MIN)));
+    assert!(matches!(value("long"), Value::I64(i64::MIN)));
+    assert_eq!(global_value(&interpreter, "grouped"), "(-128, -128)");
+    assert!(matches!(value("difference"), Value::I16(-28)));
+}
+
+#[test]
+#[should_panic(expected = "Value out of range")]
+fn test_narrow_declaration() {
+    run_source("i8 n = 128;");
+}
+
+#[test]
+fn test_sized_indices_ranges_and_keys() {
+    let interpreter: Interpreter = run_source("
+        set xs = [10, 20, 30, 40];
+        set mut ys = [1, 2, 3];
+        ys[1u8] = 5;
+        set picked = xs[3u8];
+        set last = xs[-1i64];
+        set element = (1, 2, 3)[2u16];
+        set sliced = xs[1u8..3u8];
+        set mut total = 0u8;
+        for (i in 0..3u8) total = total + i;
+        set mut narrow = 0u8;
+        for (i in 0u8..3u8) narrow = narrow + i;
+        set mut kind = \"\";
+        for (i in 0u8..1u8) kind = typeof i;
+        set mut wide = 0;
+        for (i in 2999999998..3000000000) wide = wide + 1;
+        set mut m = { 5: \"five\" };
+        m[3000000000] = \"big\";
+        set same = m[5u8];
+        m[5i64] = \"FIVE\";
+        set big = m[3000000000u64];
+        set has = 5u16 in m;
+        set mut outside = 0;
+        try { echo xs[4u8]; } catch (e) { outside = e.line; }
+    ");
+
+    assert_eq!(global_value(&interpreter, "ys"), "[1, 5, 3]");
+    assert_eq!(global_value(&interpreter, "picked"), "40");
+    assert_eq!(global_value(&interpreter, "last"), "40");
+    assert_eq!(global_value(&interpreter, "element"), "3");
+    assert_eq!(global_value(&interpreter, "sliced"), "[20, 30]");
+    assert_eq!(global_value(&interpreter, "total"), "3");
+    assert_eq!(global_value(&interpreter, "narrow"), "3");
+    assert_eq!(global_value(&interpreter, "wide"), "2");
+    assert_eq!(global_value(&interpreter, "same"), "five");
+    assert_eq!(global_value(&interpreter, "m"), "{5: FIVE, 3000000000: big}");
+    assert_eq!(global_value(&interpreter, "big"), "big");
+    assert_eq!(global_value(&interpreter, "has"), "true");
+    assert_eq!(global_value(&interpreter, "kind"), "u8");
+    assert_eq!(global_value(&interpreter, "outside"), "24");
+}
+
+#[test]
+fn test_casts() {
+    let interpreter: Interpreter = run_source("
+        set truncated = 3.9 as i32;
+        set negative = -3.9 as i32;
+        set saturated = 300 as u8;
+        set floor = -5 as u8;
+        set widened = 7 as f64;
+        f64 typed = 2 as f64;
+        set parsed = \" 42 \" as i64;
+        set parsed_float = \"2.5\" as f32;
+        set text = 1.5 as string;
+        set flag = 0 as boolean;
+        set word = \"true\" as boolean;
+        set one = true as u8;
+        set precedence = 2 * 3.7 as i32;
+        set mut failed = 0;
+        try { echo \"abc\" as i32; } catch (e) { failed = e.line; }
+    ");
+
+    let value = |name: &str| interpreter.globals.borrow().get(Token::new(TokenType::Identifier, name.to_string(), None, 0, 0)).unwrap().1.unwrap();
+    assert!(matches!(value("truncated"), Value::Integer(3)));
+    assert!(matches!(value("negative"), Value::Integer(-3)));
+    assert!(matches!(value("saturated"), Value::U8(255)));
+    assert!(matches!(value("floor"), Value::U8(0)));
+    assert!(matches!(value("widened"), Value::Float(x) if x == 7.0));
+    assert!(matches!(value("typed"), Value::Float(x) if x == 2.0));
+    assert!(matches!(value("parsed"), Value::I64(42)));
+    assert!(matches!(value("parsed_float"), Value::F32(x) if x == 2.5));
+    assert_eq!(global_value(&interpreter, "text"), "1.5");
+    assert_eq!(global_value(&interpreter, "flag"), "false");
+    assert_eq!(global_value(&interpreter, "word"), "true");
+    assert!(matches!(value("one"), Value::U8(1)));
+    assert!(matches!(value("precedence"), Value::Integer(6)));
+    assert_eq!(global_value(&interpreter, "failed"), "16");
+}
+
+#[test]
+#[should_panic(expected = "Unexpected Token")]
+fn test_cast_to_unknown_type() {
+    run_source("set n = 1 as Point;");
+}
+
+#[test]
+fn test_introspection() {
+    let interpreter: Interpreter = run_source("
+        entity Point {
+            set x = 0;
+        }
+        set p = new Point();
+        set count = 10u8;
+        set names = [typeof 1, typeof 2.5, typeof count, typeof \"a\", typeof true, typeof [1], typeof p, typeof (1, 2)];
+        set sizes = [sizeof \"rogue\", sizeof [1, 2], sizeof {}, sizeof (1,), sizeof 1, sizeof count, sizeof 1i64];
+        set name = nameof count;
+        set property = nameof p.x;
+        set describe(value) { return \"{nameof value}: {typeof value}\"; }
+        set described = describe(1.5f32);
+        set mut failed = 0;
+        try { echo sizeof true; } catch (e) { failed = e.line; }
+    ");
+
+    assert_eq!(global_value(&interpreter, "names"), "[i32, f64, u8, string, boolean, array, Point, tuple]");
+    assert_eq!(global_value(&interpreter, "sizes"), "[5, 2, 0, 1, 4, 1, 8]");
+    assert_eq!(global_value(&interpreter, "name"), "count");
+    assert_eq!(global_value(&interpreter, "property"), "x");
+    assert_eq!(global_value(&interpreter, "described"), "value: f32");
+    assert_eq!(global_value(&interpreter, "failed"), "14");
+}
+
+#[test]
+#[should_panic(expected = "after 'nameof'")]
+fn test_nameof_expression() {
+    run_source("set n = nameof 1 + 2;");
+}
+
@@ -0,0 +1,193 @@
+use crate::util::Value;
+
+/// The sized number types, named like the annotations that ask for them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NumericType {
+    I8, I16, I32, I64,
+    U8, U16, U32, U64,
+    F32, F64,
+}
+
+impl NumericType {
+    pub const INTEGERS: [NumericType; 8] = [
+        NumericType::I8, NumericType::I16, NumericType::I32, NumericType::I64,
+        NumericType::U8, NumericType::U16, NumericType::U32, NumericType::U64,
+    ];
+    pub const FLOATS: [NumericType; 2] = [NumericType::F32, NumericType::F64];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::INTEGERS.into_iter().chain(Self::FLOATS).find(|type_| type_.name() == name)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            NumericType::I8 => "i8",
+            NumericType::I16 => "i16",
+            NumericType::I32 => "i32",
+            NumericType::I64 => "i64",
+            NumericType::U8 => "u8",
+            NumericType::U16 => "u16",
+            NumericType::U32 => "u32",
+            NumericType::U64 => "u64",
+            NumericType::F32 => "f32",
+            NumericType::F64 => "f64",
+        }
+    }
+
+    pub fn of(value: &Value) -> Option<Self> {
+        match value {
+            Value::I8(_) => Some(NumericType::I8),
+            Value::I16(_) => Some(NumericType::I16),
+            Value::Integer(_) => Some(NumericType::I32),
+            Value::I64(_) => Some(NumericType::I64),
+            Value::U8(_) => Some(NumericType::U8),
+            Value::U16(_) => Some(NumericType::U16),
+            Value::U32(_) => Some(NumericType::U32),
+            Value::U64(_) => Some(NumericType::U64),
+            Value::F32(_) => Some(NumericType::F32),
+            Value::Float(_) => Some(NumericType::F64),
+            _ => None,
+        }
+    }
+
+    pub fn is_float(self) -> bool {
+        Self::FLOATS.contains(&self)
+    }
+
+    fn is_signed(self) -> bool {
+        matches!(self, NumericType::I8 | NumericType::I16 | NumericType::I32 | NumericType::I64)
+    }
+
+    pub fn bits(self) -> u32 {
+        match self {
+            NumericType::I8 | NumericType::U8 => 8,
+            NumericType::I16 | NumericType::U16 => 16,
+            NumericType::I32 | NumericType::U32 | NumericType::F32 => 32,
+            NumericType::I64 | NumericType::U64 | NumericType::F64 => 64,
+        }
+    }
+
+    /// The smallest and largest value of an integer type.
+    fn bounds(self) -> (i128, i128) {
+        match self {
+            NumericType::I8 => (i8::MIN as i128, i8::MAX as i128),
+            NumericType::I16 => (i16::MIN as i128, i16::MAX as i128),
+            NumericType::I32 => (i32::MIN as i128, i32::MAX as i128),
+            NumericType::I64 => (i64::MIN as i128, i64::MAX as i128),
+            NumericType::U8 => (0, u8::MAX as i128),
+            NumericType::U16 => (0, u16::MAX as i128),
+            NumericType::U32 => (0, u32::MAX as i128),
+            NumericType::U64 => (0, u64::MAX as i128),
+            NumericType::F32 | NumericType::F64 => (i128::MIN, i128::MAX),
+        }
+    }
+
+    fn signed(bits: u32) -> Self {
+        match bits {
+            8 => NumericType::I8,
+            16 => NumericType::I16,
+            32 => NumericType::I32,
+            _ => NumericType::I64,
+        }
+    }
+
+    /// The type a binary operation on `self` and `other` is computed in: the wider of two
+    /// integers with the same signedness, a signed type that holds both otherwise, and
+    /// the wider float as soon as a float is involved.
+    pub fn common(self, other: NumericType) -> NumericType {
+        match (self.is_float(), other.is_float()) {
+            (true, true) => if self.bits() >= other.bits() { self } else { other },
+            (true, false) => self,
+            (false, true) => other,
+            (false, false) if self.is_signed() == other.is_signed() => if self.bits() >= other.bits() { self } else { other },
+            (false, false) => {
+                let (signed, unsigned) = if self.is_signed() { (self, other) } else { (other, self) };
+                if signed.bits() > unsigned.bits() {
+                    return signed;
+                }
+                // `u64` mixed with a signed type is computed as `i64` and checked like any other overflow
+                NumericType::signed((unsigned.bits() * 2).min(64))
+            },
+        }
+    }
+}
+
+/// The value of any integer, widened so every width fits.
+pub fn integer(value: &Value) -> Option<i128> {
+    match value {
+        Value::I8(n) => Some(*n as i128),
+        Value::I16(n) => Some(*n as i128),
+        Value::Integer(n) => Some(*n as i128),
+        Value::I64(n) => Some(*n as i128),
+        Value::U8(n) => Some(*n as i128),
+        Value::U16(n) => Some(*n as i128),
+        Value::U32(n) => Some(*n as i128),
+        Value::U64(n) => Some(*n as i128),
+        _ => None,
+    }
+}
+
+/// The value of any number as a float.
+pub fn float(value: &Value) -> Option<f64> {
+    match value {
+        Value::F32(n) => Some(*n as f64),
+        Value::Float(n) => Some(*n),
+        _ => integer(value).map(|n| n as f64),
+    }
+}
+
+/// `n` as a value of the integer type `type_`, or None if it doesn't fit.
+pub fn from_integer(n: i128, type_: NumericType) -> Option<Value> {
+    match type_ {
+        NumericType::I8 => i8::try_from(n).ok().map(Value::I8),
+        NumericType::I16 => i16::try_from(n).ok().map(Value::I16),
+        NumericType::I32 => i32::try_from(n).ok().map(Value::Integer),
+        NumericType::I64 => i64::try_from(n).ok().map(Value::I64),
+        NumericType::U8 => u8::try_from(n).ok().map(Value::U8),
+        NumericType::U16 => u16::try_from(n).ok().map(Value::U16),
+        NumericType::U32 => u32::try_from(n).ok().map(Value::U32),
+        NumericType::U64 => u64::try_from(n).ok().map(Value::U64),
+        NumericType::F32 | NumericType::F64 => Some(from_float(n as f64, type_)),
+    }
+}
+
+/// `n` as the type an unsuffixed integer literal gets: `i32` unless it only fits a wider type.
+pub fn unsuffixed(n: i128) -> Option<Value> {
+    [NumericType::I32, NumericType::I64, NumericType::U64].into_iter().find_map(|type_| from_integer(n, type_))
+}
+
+/// `x` as a value of the float type `type_`.
+pub fn from_float(x: f64, type_: NumericType) -> Value {
+    match type_ {
+        NumericType::F32 => Value::F32(x as f32),
+        _ => Value::Float(x),
+    }
+}
+
+/// Converts any number to `type_` the way `as` does: floats truncate toward zero, values
+/// outside an integer type's range saturate at its bounds and NaN becomes 0.
+pub fn saturate(value: &Value, type_: NumericType) -> Option<Value> {
+    if type_.is_float() {
+        return Some(from_float(float(value)?, type_));
+    }
+
+    let n: i128 = match integer(value) {
+        Some(n) => n,
+        None => float(value)? as i128,
+    };
+    let (min, max) = type_.bounds();
+    from_integer(n.clamp(min, max), type_)
+}
+
+/// Converts a number to another width of the same kind, None if the value doesn't fit.
+pub fn convert(value: &Value, type_: NumericType) -> Option<Value> {
+    if type_.is_float() {
+        let x: f64 = float(value)?;
+        // a finite f64 beyond the range of f32 would silently become infinity
+        if type_ == NumericType::F32 && x.is_finite() && (x as f32).is_infinite() {
+            return None;
+        }
+        return Some(from_float(x, type_));
+    }
+    from_integer(integer(value)?, type_)
+}
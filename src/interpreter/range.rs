@@ -0,0 +1,79 @@
+use crate::util::Value;
+
+use super::numeric::{self, NumericType};
+
+/// Runtime value of `start..end`, `start..=end` and `start..end step n`.
+#[derive(Clone, Copy, Debug)]
+pub struct RogueRange {
+    pub start: i128,
+    pub end: i128,
+    pub step: i128,
+    pub inclusive: bool,
+    // the integer type the bounds share, iterating yields values of it
+    pub type_: NumericType,
+}
+
+impl RogueRange {
+    pub fn new(start: i128, end: i128, step: i128, inclusive: bool, type_: NumericType) -> Self {
+        Self { start, end, step, inclusive, type_ }
+    }
+
+    /// Walks from `start` towards `end`, counting down for a negative step.
+    pub fn iter(&self) -> RangeIter {
+        RangeIter { range: *self, next: Some(self.start) }
+    }
+
+    /// The numbers `iter` walks over as values of the range's type.
+    pub fn values(&self) -> impl Iterator<Item = Value> {
+        let type_: NumericType = self.type_;
+        self.iter().map_while(move |n| numeric::from_integer(n, type_))
+    }
+}
+
+// like numbers, ranges with the same bounds are equal whatever width they were written in
+impl PartialEq for RogueRange {
+    fn eq(&self, other: &Self) -> bool {
+        (self.start, self.end, self.step, self.inclusive) == (other.start, other.end, other.step, other.inclusive)
+    }
+}
+
+impl core::fmt::Display for RogueRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let op: &str = if self.inclusive { "..=" } else { ".." };
+        write!(f, "{}{op}{}", self.start, self.end)?;
+
+        if self.step != 1 {
+            write!(f, " step {}", self.step)?;
+        }
+        Ok(())
+    }
+}
+
+pub struct RangeIter {
+    range: RogueRange,
+    next: Option<i128>,
+}
+
+impl Iterator for RangeIter {
+    type Item = i128;
+
+    fn next(&mut self) -> Option<i128> {
+        let current: i128 = self.next?;
+        let RogueRange { end, step, inclusive, .. } = self.range;
+
+        let in_range: bool = match (step > 0, inclusive) {
+            (true, true) => current <= end,
+            (true, false) => current < end,
+            (false, true) => current >= end,
+            (false, false) => current > end,
+        };
+        if !in_range {
+            self.next = None;
+            return None;
+        }
+
+        // stops instead of wrapping around at the ends of i128
+        self.next = current.checked_add(step);
+        Some(current)
+    }
+}
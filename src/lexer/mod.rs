@@ -6,12 +6,18 @@ use std::{collections::HashMap, fmt::Debug};
 use tokens::{Token, TokenType};
 
 // use crate::util::{Number, Object};
-use crate::util::Value;
+use crate::interpreter::numeric::{self, NumericType};
+use crate::util::{error_formatter::{ErrorHandler, ErrorKind}, Value};
 
 fn error(line: usize, msg: &str) -> ! {
     crate::report(line, "", msg)
 }
 
+fn escape_error(line: usize, row: usize, escape: String, msg: &'static str) -> ! {
+    let error_handler: ErrorHandler = ErrorHandler;
+    error_handler.throw(ErrorKind::InvalidEscape(Token::new(TokenType::String, escape, None, line, row), msg));
+}
+
 macro_rules! map {
     ($($key:expr => $value:expr),*) => {
         {
@@ -97,6 +103,10 @@ impl Lexer {
                 "varargs" => TokenType::Varargs,
                 "test" => TokenType::Test,
                 "move" => TokenType::Move,
+                "return" => TokenType::Return,
+                "match" => TokenType::Match,
+                "break" => TokenType::Break,
+                "continue" => TokenType::Continue,
                 "and" => TokenType::And,
                 "or" => TokenType::Or
             }
@@ -159,6 +169,10 @@ impl Lexer {
         self.source.chars().nth(self.current + 1).unwrap()
     }
 
+    fn closing_quotes(&self, quotes: usize) -> bool {
+        (0..quotes).all(|offset| self.source.chars().nth(self.current + offset) == Some('"'))
+    }
+
     fn scan_token(&mut self) {
         println!("Checking: {}", self.source.chars().nth(self.current).unwrap());
         println!("row: {}", self.row);
@@ -172,7 +186,16 @@ impl Lexer {
             '[' => self.add_token(TokenType::LSquare),
             ']' => self.add_token(TokenType::RSquare),
             ',' => self.add_token(TokenType::Comma),
-            '.' => self.add_token(TokenType::Dot),
+            '.' => {
+                if self.expect('.') {
+                    match self.expect('=') {
+                        true => self.add_token(TokenType::DotDotEqual),
+                        _ => self.add_token(TokenType::DotDot)
+                    }
+                } else {
+                    self.add_token(TokenType::Dot)
+                }
+            },
             '-' => {
                 if self.expect('=') {
                     self.add_token(TokenType::MinusEqual)
@@ -191,10 +214,16 @@ impl Lexer {
                 true => self.add_token(TokenType::PlusEqual),
                 _ => self.add_token(TokenType::Plus)
             },
-            '/' => match self.expect('=') {
-                true => self.add_token(TokenType::DivEqual),
-                _ => self.add_token(TokenType::Slash)
+            '/' => {
+                if self.expect('/') {
+                    self.add_token(TokenType::SlashSlash)
+                } else if self.expect('=') {
+                    self.add_token(TokenType::DivEqual)
+                } else {
+                    self.add_token(TokenType::Slash)
+                }
             },
+            '%' => self.add_token(TokenType::Percent),
             '*' => match self.expect('=') {
                 true => self.add_token(TokenType::TimesEqual),
                 _ => self.add_token(TokenType::Star)
@@ -223,7 +252,11 @@ impl Lexer {
                 self.line += 1;
                 self.row = 0;
             },
-            '"' => self.string(),
+            '"' => self.string(false),
+            'r' if self.peek() == '"' => {
+                self.next();
+                self.string(true);
+            },
             _ if is_digit(c) => self.number(),
             _ if is_alpha(c) => self.identifier(),
             _ => {
@@ -232,46 +265,292 @@ impl Lexer {
         }
     }
 
-    fn string(&mut self) {
-        while self.peek() != '"' && !self.eof() {
-            if self.peek() == '\n' {
-                self.line += 1;
+    /// Lexes `"..."`, a raw `r"..."` or a multi-line `"""..."""`, the opening quote is already consumed.
+    /// A string with `{expression}` holes becomes `StringPart` tokens around the expressions' own
+    /// tokens, ending with a `String` token for the text after the last hole.
+    fn string(&mut self, raw: bool) {
+        let start: usize = self.start;
+        let triple: bool = self.peek() == '"' && self.peek_next() == '"';
+        let quotes: usize = if triple { 3 } else { 1 };
+        if triple {
+            self.next();
+            self.next();
+        }
+
+        let mut pieces: Vec<Piece> = Vec::new();
+        let mut interpolations: Vec<Vec<Token>> = Vec::new();
+        while !self.closing_quotes(quotes) {
+            if self.eof() {
+                error(self.line, "Unterminated String");
+            }
+
+            let c: char = self.next();
+            match c {
+                '\\' if !raw => pieces.push(Piece::Escaped(self.escape())),
+                '{' if !raw => {
+                    pieces.push(Piece::Interpolation);
+                    interpolations.push(self.interpolation());
+                },
+                '\n' => {
+                    self.line += 1;
+                    self.row = 0;
+                    pieces.push(Piece::Literal(c));
+                },
+                _ => pieces.push(Piece::Literal(c)),
             }
+        }
+        for _ in 0..quotes {
             self.next();
         }
 
+        if triple {
+            pieces = dedent(pieces);
+        }
+
+        let mut parts: Vec<String> = vec![String::new()];
+        for piece in pieces {
+            match piece {
+                Piece::Literal(c) | Piece::Escaped(c) => parts.last_mut().unwrap().push(c),
+                Piece::Interpolation => parts.push(String::new()),
+            }
+        }
+
+        self.start = start;
+        let last: String = parts.pop().unwrap();
+        for (part, tokens) in parts.into_iter().zip(interpolations) {
+            self.add_token_lit(TokenType::StringPart, Some(Value::String(part)));
+            self.tokens.extend(tokens);
+        }
+        self.add_token_lit(TokenType::String, Some(Value::String(last)));
+    }
+
+    /// Lexes the expression of a `{...}` hole up to its closing brace, the opening one is already consumed.
+    fn interpolation(&mut self) -> Vec<Token> {
+        let outer: Vec<Token> = std::mem::take(&mut self.tokens);
+        let mut depth: usize = 0;
+
+        loop {
+            if self.eof() {
+                error(self.line, "Unterminated interpolation, expected '}'");
+            }
+            if self.peek() == '}' && depth == 0 {
+                self.next();
+                break;
+            }
+
+            self.start = self.current;
+            self.scan_token();
+            match &self.source[self.start..self.current] {
+                "{" => depth += 1,
+                "}" => depth -= 1,
+                _ => {},
+            }
+        }
+
+        std::mem::replace(&mut self.tokens, outer)
+    }
+
+    /// The character an escape sequence stands for, the backslash is already consumed.
+    fn escape(&mut self) -> char {
+        // the backslash's column
+        let (line, row) = (self.line, self.row);
         if self.eof() {
             error(self.line, "Unterminated String");
         }
-        self.next();
 
-        let value: String = self.source[(self.start + 1)..(self.current - 1)].to_string();
-        self.add_token_lit(TokenType::String, Some(Value::String(value)));
+        match self.next() {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            '\\' => '\\',
+            '"' => '"',
+            '\'' => '\'',
+            '{' => '{',
+            '}' => '}',
+            'u' => self.unicode_escape(line, row),
+            c => escape_error(line, row, format!("\\{c}"), "Unknown escape sequence."),
+        }
+    }
+
+    /// `\u{1F600}`, one to six hex digits naming a unicode scalar value.
+    fn unicode_escape(&mut self, line: usize, row: usize) -> char {
+        let mut escape: String = String::from("\\u");
+        if !self.expect('{') {
+            escape_error(line, row, escape, "Expected `{` after `\\u`.");
+        }
+        escape.push('{');
+
+        let mut digits: String = String::new();
+        while self.peek().is_ascii_hexdigit() && digits.len() < 6 {
+            digits.push(self.next());
+        }
+        escape.push_str(&digits);
+
+        if !self.expect('}') {
+            escape_error(line, row, escape, "Expected one to six hex digits and a closing `}`.");
+        }
+        escape.push('}');
+
+        match u32::from_str_radix(&digits, 16).ok().and_then(char::from_u32) {
+            Some(c) => c,
+            None => escape_error(line, row, escape, "Not a valid unicode scalar value."),
+        }
     }
 
     fn number(&mut self) {
+        let first: char = self.source.chars().nth(self.start).unwrap();
+        if first == '0' && matches!(self.peek(), 'x' | 'b' | 'o') {
+            return self.radix_number();
+        }
+
         let mut is_f64: bool = false;
+        self.digits(10);
 
-        while is_digit(self.peek()) {
-            println!("current = {}", self.source.chars().nth(self.current).unwrap());
+        if self.peek() == '.' && is_digit(self.peek_next()) {
+            is_f64 = true;
             self.next();
+            self.digits(10);
         }
 
-        if self.peek() == '.' && is_digit(self.peek_next()) {
+        // `6.02e23`, `1e-9`
+        if matches!(self.peek(), 'e' | 'E') && (is_digit(self.peek_next()) || matches!(self.peek_next(), '+' | '-')) {
             is_f64 = true;
             self.next();
-
-            while is_digit(self.peek()) {
+            if matches!(self.peek(), '+' | '-') {
                 self.next();
             }
+            if !is_digit(self.peek()) {
+                self.number_error("Expected digits in the exponent.");
+            }
+            self.digits(10);
+        }
+
+        let text: String = self.source[self.start..self.current].to_string();
+        let suffix: Option<NumericType> = self.suffix();
+        let literal: Value = self.number_literal(&text, &text.replace('_', ""), 10, is_f64, suffix);
+        self.add_token_lit(TokenType::Number, Some(literal));
+    }
+
+    /// `0xFF`, `0b1010` and `0o17`, the leading `0` is already consumed.
+    fn radix_number(&mut self) {
+        let (radix, name): (u32, &str) = match self.next() {
+            'x' => (16, "hexadecimal"),
+            'b' => (2, "binary"),
+            _ => (8, "octal"),
+        };
+
+        let digits_start: usize = self.current;
+        self.digits(radix);
+        let digits: String = self.source[digits_start..self.current].replace('_', "");
+
+        if is_digit(self.peek()) {
+            self.number_error(&format!("Invalid digit `{}` in a {name} literal.", self.peek()));
+        }
+        if digits.is_empty() {
+            self.number_error(&format!("Expected {name} digits after `{}`.", &self.source[self.start..digits_start]));
+        }
+
+        let text: String = self.source[self.start..self.current].to_string();
+        let suffix: Option<NumericType> = self.suffix();
+        if suffix.is_some_and(NumericType::is_float) {
+            self.number_error(&format!("A {name} literal can't have a float suffix."));
+        }
+
+        let literal: Value = self.number_literal(&text, &digits, radix, false, suffix);
+        self.add_token_lit(TokenType::Number, Some(literal));
+    }
+
+    /// Consumes digits of `radix` along with `_` separators.
+    fn digits(&mut self, radix: u32) {
+        while self.peek().is_digit(radix) || self.peek() == '_' {
+            self.next();
+        }
+    }
+
+    fn number_literal(&mut self, text: &str, digits: &str, radix: u32, is_f64: bool, suffix: Option<NumericType>) -> Value {
+        if is_f64 {
+            if let Some(type_) = suffix.filter(|type_| !type_.is_float()) {
+                self.number_error(&format!("A literal with a fraction or an exponent can't have the integer suffix `{}`.", type_.name()));
+            }
+
+            // too large a literal parses as infinity instead of failing
+            let type_: NumericType = suffix.unwrap_or(NumericType::F64);
+            return match digits.parse::<f64>() {
+                Ok(x) if x.is_finite() => match numeric::convert(&Value::Float(x), type_) {
+                    Some(literal) => literal,
+                    None => self.literal_error(text, type_),
+                },
+                Ok(_) => self.literal_error(text, type_),
+                Err(_) => self.number_error("Malformed float literal."),
+            };
+        }
+
+        let n: Option<i128> = i128::from_str_radix(digits, radix).ok();
+        let literal: Option<Value> = match suffix {
+            Some(type_) => n.and_then(|n| numeric::from_integer(n, type_)),
+            None => n.and_then(numeric::unsuffixed),
+        };
+
+        if let Some(literal) = literal {
+            return literal;
+        }
+
+        // `-128i8`: only the negated magnitude fits, so the minus becomes part of the literal
+        let negative: Option<Value> = n.zip(suffix).filter(|_| self.negates_literal())
+            .and_then(|(n, type_)| numeric::from_integer(-n, type_));
+        match negative {
+            Some(literal) => {
+                self.tokens.pop();
+                self.start -= 1;
+                literal
+            },
+            None => self.literal_error(text, suffix.unwrap_or(NumericType::U64)),
         }
+    }
 
-        let value: String = self.source[self.start..self.current].to_string();
+    /// Whether the literal being lexed directly follows a `-` that negates it rather than
+    /// subtracting it from an operand.
+    fn negates_literal(&self) -> bool {
+        let follows_minus: bool = self.start > 0 && self.source.as_bytes()[self.start - 1] == b'-'
+            && self.tokens.last().is_some_and(|token| token.type_ == TokenType::Minus);
+        let operand: bool = self.tokens.len() >= 2 && matches!(self.tokens[self.tokens.len() - 2].type_,
+            TokenType::Number | TokenType::String | TokenType::Identifier | TokenType::True | TokenType::False
+            | TokenType::This | TokenType::RParen | TokenType::RSquare | TokenType::RBrace);
+        follows_minus && !operand
+    }
 
-        match is_f64 {
-            true => self.add_token_lit(TokenType::Number, Some(Value::Float(value.parse::<f64>().unwrap()))),
-            false => self.add_token_lit(TokenType::Number, Some(Value::Integer(value.parse::<i32>().unwrap()))), 
+    /// Consumes a type suffix like the `u8` in `10u8`, any other letters glued to a number are an error.
+    fn suffix(&mut self) -> Option<NumericType> {
+        let mut end: usize = self.current;
+        while self.source.chars().nth(end).is_some_and(is_alphanumeric) {
+            end += 1;
+        }
+        if end == self.current {
+            return None;
         }
+
+        let text: &str = &self.source[self.current..end];
+        let Some(type_) = NumericType::from_name(text) else {
+            self.number_error(&format!("Invalid suffix `{text}`, expected a type like `u8` or `f32`."));
+        };
+        while self.current < end {
+            self.next();
+        }
+        Some(type_)
+    }
+
+    fn literal_error(&self, value: &str, type_: NumericType) -> ! {
+        let literal: Token = Token::new(TokenType::Number, self.source[self.start..self.current].to_string(), None, self.line, self.row);
+        let error_handler: ErrorHandler = ErrorHandler;
+        error_handler.throw(ErrorKind::OutOfRange(literal, value.to_string(), type_.name()));
+    }
+
+    /// Reports a malformed number literal at the character the lexer stopped at.
+    fn number_error(&self, msg: &str) -> ! {
+        let literal: Token = Token::new(TokenType::Number, self.source[self.start..self.current].to_string(), None, self.line, self.row + 1);
+        let error_handler: ErrorHandler = ErrorHandler;
+        error_handler.throw(ErrorKind::MalformedNumber(literal, msg.to_string()));
     }
 
     fn identifier(&mut self) {
@@ -289,6 +568,63 @@ impl Lexer {
     }
 }
 
+/// Whether the lexeme of a number literal ends in a type suffix, hexadecimal digits
+/// include `f` so only integer suffixes can follow them.
+pub fn has_suffix(lexeme: &str) -> bool {
+    let lexeme: &str = lexeme.trim_start_matches('-');
+    match lexeme.get(..2) {
+        Some("0x") => lexeme[2..].contains(['i', 'u']),
+        Some("0b" | "0o") => lexeme[2..].contains(is_alpha),
+        _ => lexeme.contains(['i', 'u', 'f']),
+    }
+}
+
+/// One character of a string literal, escapes are kept apart so `dedent` leaves an escaped newline or tab alone.
+#[derive(Clone, Copy)]
+enum Piece {
+    Literal(char),
+    Escaped(char),
+    // where a `{...}` hole was
+    Interpolation,
+}
+
+/// Strips the indentation all lines of a multi-line string share, along with a
+/// blank first and last line so the quotes can sit on lines of their own.
+fn dedent(pieces: Vec<Piece>) -> Vec<Piece> {
+    let mut lines: Vec<Vec<Piece>> = vec![Vec::new()];
+    for piece in pieces {
+        match piece {
+            Piece::Literal('\n') => lines.push(Vec::new()),
+            _ => lines.last_mut().unwrap().push(piece),
+        }
+    }
+
+    let is_indent = |piece: &&Piece| matches!(piece, Piece::Literal(' ' | '\t'));
+    let is_blank = |line: &Vec<Piece>| line.iter().all(|piece| is_indent(&piece));
+
+    if lines.len() > 1 && is_blank(&lines[0]) {
+        lines.remove(0);
+    }
+    if lines.len() > 1 && is_blank(lines.last().unwrap()) {
+        lines.pop();
+    }
+
+    let indent: usize = lines.iter()
+        .filter(|line| !is_blank(line))
+        .map(|line| line.iter().take_while(is_indent).count())
+        .min()
+        .unwrap_or(0);
+
+    let mut dedented: Vec<Piece> = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        if i > 0 {
+            dedented.push(Piece::Literal('\n'));
+        }
+        dedented.extend(line.iter().skip(indent));
+    }
+    dedented
+}
+
 fn is_digit(c: char) -> bool {
     c >= '0' && c <= '9'
 }
@@ -299,4 +635,134 @@ fn is_alpha(c: char) -> bool {
 
 fn is_alphanumeric(c: char) -> bool {
     is_digit(c) || is_alpha(c)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lex(source: &str) -> Vec<Token> {
+        Lexer::new(source.to_string()).tokenize()
+    }
+
+    /// The value of a source that is a single literal.
+    fn literal(source: &str) -> Value {
+        let tokens: Vec<Token> = lex(source);
+        assert_eq!(tokens.len(), 2, "`{source}` should be one token");
+        tokens[0].literal.clone().unwrap()
+    }
+
+    /// Token types paired with the text of string literals.
+    fn strings(source: &str) -> Vec<(TokenType, String)> {
+        lex(source).into_iter()
+            .map(|token| match token.literal {
+                Some(Value::String(text)) => (token.type_, text),
+                _ => (token.type_, token.lexeme),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_number_literals() {
+        assert!(matches!(literal("0xFF"), Value::Integer(255)));
+        assert!(matches!(literal("0b1010_1010"), Value::Integer(170)));
+        assert!(matches!(literal("0o17"), Value::Integer(15)));
+        assert!(matches!(literal("1_000_000"), Value::Integer(1000000)));
+        assert!(matches!(literal("6.02e23"), Value::Float(x) if x == 6.02e23));
+        assert!(matches!(literal("1E-3"), Value::Float(x) if x == 0.001));
+        assert!(matches!(literal("0xFFu8"), Value::U8(255)));
+        assert!(matches!(literal("0xFFFF_FFFF"), Value::I64(4294967295)));
+        assert!(matches!(literal("2.5f32"), Value::F32(x) if x == 2.5));
+    }
+
+    #[test]
+    fn test_number_suffixes() {
+        assert!(has_suffix("10u8"));
+        assert!(has_suffix("-128i8"));
+        assert!(has_suffix("1e5f32"));
+        assert!(has_suffix("0xFFu16"));
+        assert!(!has_suffix("0x1f32"));
+        assert!(!has_suffix("1e5"));
+        assert!(!has_suffix("0b1010"));
+    }
+
+    #[test]
+    fn test_signed_minimum_literal() {
+        let tokens: Vec<Token> = lex("set n = -128i8;");
+        assert_eq!(tokens[3].type_, TokenType::Number);
+        assert_eq!(tokens[3].lexeme, "-128i8");
+        assert!(matches!(tokens[3].literal, Some(Value::I8(i8::MIN))));
+
+        // a minus that fits the literal without it stays an operator
+        let tokens: Vec<Token> = lex("x -5i8");
+        assert_eq!(tokens[1].type_, TokenType::Minus);
+        assert!(matches!(tokens[2].literal, Some(Value::I8(5))));
+    }
+
+    #[test]
+    #[should_panic(expected = "Value out of range")]
+    fn test_subtracted_signed_minimum() {
+        lex("x -128i8");
+    }
+
+    #[test]
+    #[should_panic(expected = "Malformed number literal")]
+    fn test_malformed_number() {
+        lex("0b102");
+    }
+
+    #[test]
+    #[should_panic(expected = "Malformed number literal")]
+    fn test_number_suffix() {
+        lex("12abc");
+    }
+
+    #[test]
+    #[should_panic(expected = "Malformed number literal")]
+    fn test_fraction_with_integer_suffix() {
+        lex("2.5i32");
+    }
+
+    #[test]
+    #[should_panic(expected = "Value out of range")]
+    fn test_infinite_float_literal() {
+        lex("1e400");
+    }
+
+    #[test]
+    #[should_panic(expected = "Value out of range")]
+    fn test_f32_literal_out_of_range() {
+        lex("1e39f32");
+    }
+
+    #[test]
+    fn test_string_escapes() {
+        assert_eq!(literal(r#""tab\there\nquote \" slash \\ brace \{""#).to_string(), "tab\there\nquote \" slash \\ brace {");
+        assert_eq!(literal(r#""\u{1F600}""#).to_string(), "\u{1F600}");
+        assert_eq!(literal(r#"r"C:\new\{dir}""#).to_string(), r"C:\new\{dir}");
+        assert_eq!(literal("\"\"\"\n    roses are red\n      violets are blue\\t\n    \"\"\"").to_string(), "roses are red\n  violets are blue\t");
+        assert_eq!(literal(r#""""one "quoted" line""""#).to_string(), "one \"quoted\" line");
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid escape sequence")]
+    fn test_invalid_escape() {
+        lex(r#""bad \q escape""#);
+    }
+
+    #[test]
+    fn test_interpolation() {
+        let part = |text: &str| (TokenType::StringPart, text.to_string());
+        let string = |text: &str| (TokenType::String, text.to_string());
+        let name = (TokenType::Identifier, "name".to_string());
+        let eof = (TokenType::EOF, String::new());
+
+        assert_eq!(strings(r#""x = {name}""#), vec![part("x = "), name.clone(), string(""), eof.clone()]);
+        assert_eq!(strings(r#""outer {"inner {name}"}!""#), vec![part("outer "), part("inner "), name.clone(), string(""), string("!"), eof.clone()]);
+        assert_eq!(strings(r#""\{name} stays""#), vec![string("{name} stays"), eof.clone()]);
+        assert_eq!(
+            strings("\"\"\"\n    name: {name}\n      again: {name}\n    \"\"\""),
+            vec![part("name: "), name.clone(), part("\n  again: "), name, string(""), eof],
+        );
+    }
+}
@@ -9,6 +9,10 @@ use crate::util::Value;
 pub enum TokenType {
     LParen, RParen, LBrace, RBrace, LSquare, RSquare, Comma, Dot, Minus, Plus, Semicolon, Slash, Star,
 
+    SlashSlash, Percent,
+
+    DotDot, DotDotEqual,
+
     Arrow, Colon, Questionmark, Exclaimationmark,  
 
     MinusEqual, PlusEqual,
@@ -19,12 +23,12 @@ pub enum TokenType {
     Greater, GreaterEqual,
     Less, LessEqual,
 
-    Identifier, String, Number,
+    Identifier, String, StringPart, Number,
 
     Mut, Typeof, Sizeof, Nameof, As, Void, Use, With, Out, True, False, If, Elif, Else, While, For, 
     In, Entity, Init, New, This, Set, Enum, Throw, Catch, Pub, Priv, Prot, Unreachable, Trait, Parent, 
     Open, Override, Scene, Import, Todo, Pass, Echo, Try, Await, Thread, Worker, Chan, Select, Pool, Defer, 
-    Macro, Vararg, Varargs, Test, Move,
+    Macro, Vararg, Varargs, Test, Move, Return, Match, Break, Continue,
 
     And, Or,
 
@@ -3,9 +3,11 @@ use std::{fs, io::{self, stdin, BufRead, BufReader}};
 use interpreter::Interpreter;
 use lexer::{tokens::Token, Lexer};
 use parser::{ast::{Expr, Stmt}, Parser};
+use resolver::Resolver;
 
 pub mod lexer;
 pub mod parser;
+pub mod resolver;
 pub mod interpreter;
 pub mod util;
 
@@ -65,7 +67,10 @@ fn run(source: String) {
     let mut parser: Parser = Parser::new(tokens);
     let stmts: Vec<Box<dyn Stmt>> = parser.parse();
 
-    // println!("expr = {}", AstPrinter().print(&*expr));
+    let mut resolver: Resolver = Resolver::new();
+    resolver.resolve(&stmts);
+
+    // println!("{}", parser::ast_printer::AstPrinter::new().print(&stmts));
 
     let mut interpreter: Interpreter = Interpreter::new();
     interpreter.interpret(stmts);
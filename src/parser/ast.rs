@@ -1,5 +1,5 @@
 
-use std::{any::Any, ffi::FromVecWithNulError};
+use std::{any::Any, cell::{Cell, RefCell}, collections::HashMap, ffi::FromVecWithNulError, rc::Rc};
 
 use crate::{lexer::tokens::Token, /*util::Object*/ util::Value};
 
@@ -7,23 +7,52 @@ pub trait Visitor {
     fn visit_binary(&mut self, binary: &Binary) -> Value;
     fn visit_grouping(&mut self, grouping: &Grouping) -> Value;
     fn visit_literal(&self, literal: &Literal) -> Value;
+    fn visit_interpolation(&mut self, interpolation: &Interpolation) -> Value;
     fn visit_unary(&mut self, unary: &Unary) -> Value;
+    fn visit_cast(&mut self, cast: &Cast) -> Value;
     fn visit_logical(&mut self, logical: &Logical) -> Value;
-    fn visit_variable(&self, variable: &Variable) -> Value;
+    fn visit_variable(&mut self, variable: &Variable) -> Value;
     fn visit_assign(&mut self, assign: &Assign) -> Value;
+    fn visit_call(&mut self, call: &Call) -> Value;
+    fn visit_get(&mut self, get: &Get) -> Value;
+    fn visit_set(&mut self, set: &Set) -> Value;
+    fn visit_this(&mut self, this: &This) -> Value;
+    fn visit_new(&mut self, new: &New) -> Value;
+    fn visit_parent(&mut self, parent: &Parent) -> Value;
+    fn visit_range(&mut self, range: &Range) -> Value;
+    fn visit_array(&mut self, array: &Array) -> Value;
+    fn visit_map(&mut self, map: &Map) -> Value;
+    fn visit_tuple(&mut self, tuple: &Tuple) -> Value;
+    fn visit_index(&mut self, index: &Index) -> Value;
+    fn visit_set_index(&mut self, set_index: &SetIndex) -> Value;
 
     fn visit_expr_stmt(&mut self, expr: &Expression);
     fn visit_echo_stmt(&mut self, echo: &Echo);
     fn visit_block_stmt(&mut self, block: &Block);
     fn visit_if_stmt(&mut self, if_: &If);
     fn visit_while_stmt(&mut self, while_: &While);
+    fn visit_for_stmt(&mut self, for_: &For);
+    fn visit_break_stmt(&mut self, break_: &Break);
+    fn visit_continue_stmt(&mut self, continue_: &Continue);
+    fn visit_return_stmt(&mut self, return_: &Return);
+    fn visit_match_stmt(&mut self, match_: &Match);
+    fn visit_throw_stmt(&mut self, throw: &Throw);
+    fn visit_try_stmt(&mut self, try_: &Try);
+    fn visit_defer_stmt(&mut self, defer: &Defer);
     
     fn visit_var_decl(&mut self, var: &Var);
+    fn visit_destructure_decl(&mut self, destructure: &Destructure);
+    fn visit_function_decl(&mut self, function: &Function);
+    fn visit_entity_decl(&mut self, entity: &Entity);
+    fn visit_trait_decl(&mut self, trait_: &Trait);
+    fn visit_enum_decl(&mut self, enum_: &Enum);
+    fn visit_export_stmt(&mut self, export: &Export);
 }
 
 pub trait Expr {
     fn accept(&self, visitor: &mut dyn Visitor) -> Value;
     fn as_any(&self) -> &dyn Any;
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
 }
 
 pub struct Binary {
@@ -45,6 +74,9 @@ impl Expr for Binary {
     fn as_any(&self) -> &dyn Any {
         self
     }
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
 }
 
 pub struct Grouping {
@@ -63,15 +95,24 @@ impl Expr for Grouping {
     fn as_any(&self) -> &dyn Any {
         self
     }
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
 }
 
 pub struct Literal {
     pub value: Option<Value>,
+    // a number without a suffix, it takes the type of the number it's combined with
+    pub untyped: bool,
 }
 
 impl Literal {
     pub fn new(value: Option<Value>) -> Self {
-        Self { value }
+        Self { value, untyped: false }
+    }
+
+    pub fn number(value: Option<Value>, untyped: bool) -> Self {
+        Self { value, untyped }
     }
 }
 impl Expr for Literal {
@@ -81,6 +122,56 @@ impl Expr for Literal {
     fn as_any(&self) -> &dyn Any {
         self
     }
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+/// `"x = {x + 1}"`, the literal text and the expressions of its holes in source order.
+pub struct Interpolation {
+    pub parts: Vec<Box<dyn Expr>>,
+}
+
+impl Interpolation {
+    pub fn new(parts: Vec<Box<dyn Expr>>) -> Self {
+        Self { parts }
+    }
+}
+impl Expr for Interpolation {
+    fn accept(&self, visitor: &mut dyn Visitor) -> Value {
+        visitor.visit_interpolation(self)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+/// `value as type_`
+pub struct Cast {
+    pub value: Box<dyn Expr>,
+    pub keyword: Token,
+    pub type_: Token,
+}
+
+impl Cast {
+    pub fn new(value: Box<dyn Expr>, keyword: Token, type_: Token) -> Self {
+        Self { value, keyword, type_ }
+    }
+}
+
+impl Expr for Cast {
+    fn accept(&self, visitor: &mut dyn Visitor) -> Value {
+        visitor.visit_cast(self)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
 }
 
 pub struct Logical {
@@ -102,6 +193,9 @@ impl Expr for Logical {
     fn as_any(&self) -> &dyn Any {
         self 
     }
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
 }
 
 pub struct Unary {
@@ -122,16 +216,35 @@ impl Expr for Unary {
     fn as_any(&self) -> &dyn Any {
         self
     }
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+/// Where a local variable lives, filled in by the resolver: `depth` scopes up
+/// from the current one, at index `slot` of that scope.
+/// Globals stay unbound and are looked up by name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Binding {
+    pub depth: usize,
+    pub slot: usize,
+}
+
+impl Binding {
+    pub fn new(depth: usize, slot: usize) -> Self {
+        Self { depth, slot }
+    }
 }
 
 #[derive(Debug)]
 pub struct Variable {
     pub name: Token,
+    pub binding: Cell<Option<Binding>>,
 }
 
 impl Variable {
     pub fn new(name: Token) -> Self {
-        Self { name }
+        Self { name, binding: Cell::new(None) }
     }
 }
 
@@ -142,17 +255,21 @@ impl Expr for Variable {
     fn as_any(&self) -> &dyn Any {
         self
     }
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
 }
 
 
 pub struct Assign {
     pub name: Token,
     pub value: Box<dyn Expr>,
+    pub binding: Cell<Option<Binding>>,
 }
 
 impl Assign {
     pub fn new(name: Token, value: Box<dyn Expr>) -> Self {
-        Self { name, value }
+        Self { name, value, binding: Cell::new(None) }
     }
 }
 
@@ -163,11 +280,303 @@ impl Expr for Assign {
     fn as_any(&self) -> &dyn Any {
         self
     }
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+pub struct Call {
+    pub callee: Box<dyn Expr>,
+    pub paren: Token,
+    pub arguments: Vec<Box<dyn Expr>>,
+}
+
+impl Call {
+    pub fn new(callee: Box<dyn Expr>, paren: Token, arguments: Vec<Box<dyn Expr>>) -> Self {
+        Self { callee, paren, arguments }
+    }
+}
+
+impl Expr for Call {
+    fn accept(&self, visitor: &mut dyn Visitor) -> Value {
+        visitor.visit_call(self)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+pub struct Get {
+    pub object: Box<dyn Expr>,
+    pub name: Token,
+    // name of the entity whose body this access is written in, filled in by the resolver
+    pub accessor: RefCell<Option<String>>,
+}
+
+impl Get {
+    pub fn new(object: Box<dyn Expr>, name: Token) -> Self {
+        Self { object, name, accessor: RefCell::new(None) }
+    }
+}
+
+impl Expr for Get {
+    fn accept(&self, visitor: &mut dyn Visitor) -> Value {
+        visitor.visit_get(self)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+pub struct Set {
+    pub object: Box<dyn Expr>,
+    pub name: Token,
+    pub value: Box<dyn Expr>,
+    pub accessor: RefCell<Option<String>>,
+}
+
+impl Set {
+    pub fn new(object: Box<dyn Expr>, name: Token, value: Box<dyn Expr>) -> Self {
+        Self { object, name, value, accessor: RefCell::new(None) }
+    }
+}
+
+impl Expr for Set {
+    fn accept(&self, visitor: &mut dyn Visitor) -> Value {
+        visitor.visit_set(self)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+pub struct This {
+    pub keyword: Token,
+    pub binding: Cell<Option<Binding>>,
+}
+
+impl This {
+    pub fn new(keyword: Token) -> Self {
+        Self { keyword, binding: Cell::new(None) }
+    }
+}
+
+impl Expr for This {
+    fn accept(&self, visitor: &mut dyn Visitor) -> Value {
+        visitor.visit_this(self)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+pub struct New {
+    pub keyword: Token,
+    pub entity: Box<dyn Expr>,
+    pub arguments: Vec<Box<dyn Expr>>,
+}
+
+impl Expr for New {
+    fn accept(&self, visitor: &mut dyn Visitor) -> Value {
+        visitor.visit_new(self)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+pub struct Parent {
+    pub keyword: Token,
+    pub method: Token,
+    pub binding: Cell<Option<Binding>>,
+}
+
+impl Parent {
+    pub fn new(keyword: Token, method: Token) -> Self {
+        Self { keyword, method, binding: Cell::new(None) }
+    }
+}
+
+impl Expr for Parent {
+    fn accept(&self, visitor: &mut dyn Visitor) -> Value {
+        visitor.visit_parent(self)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+/// `start..end` or `start..=end`, optionally followed by `step expr`.
+pub struct Range {
+    pub start: Box<dyn Expr>,
+    pub operator: Token,
+    pub end: Box<dyn Expr>,
+    pub step: Option<Box<dyn Expr>>,
+}
+
+impl Range {
+    pub fn new(start: Box<dyn Expr>, operator: Token, end: Box<dyn Expr>, step: Option<Box<dyn Expr>>) -> Self {
+        Self { start, operator, end, step }
+    }
+}
+
+impl Expr for Range {
+    fn accept(&self, visitor: &mut dyn Visitor) -> Value {
+        visitor.visit_range(self)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+/// `[a, b, c]`
+pub struct Array {
+    pub bracket: Token,
+    pub elements: Vec<Box<dyn Expr>>,
+}
+
+impl Array {
+    pub fn new(bracket: Token, elements: Vec<Box<dyn Expr>>) -> Self {
+        Self { bracket, elements }
+    }
+}
+
+impl Expr for Array {
+    fn accept(&self, visitor: &mut dyn Visitor) -> Value {
+        visitor.visit_array(self)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+/// `(a, b, c)`, a single element needs a trailing comma to not be a `Grouping`.
+pub struct Tuple {
+    pub paren: Token,
+    pub elements: Vec<Box<dyn Expr>>,
+}
+
+impl Tuple {
+    pub fn new(paren: Token, elements: Vec<Box<dyn Expr>>) -> Self {
+        Self { paren, elements }
+    }
+}
+
+impl Expr for Tuple {
+    fn accept(&self, visitor: &mut dyn Visitor) -> Value {
+        visitor.visit_tuple(self)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+/// `{ key: value, ... }`, only parsed where an expression is expected so it never clashes with blocks.
+pub struct Map {
+    pub brace: Token,
+    pub entries: Vec<(Box<dyn Expr>, Box<dyn Expr>)>,
+}
+
+impl Map {
+    pub fn new(brace: Token, entries: Vec<(Box<dyn Expr>, Box<dyn Expr>)>) -> Self {
+        Self { brace, entries }
+    }
+}
+
+impl Expr for Map {
+    fn accept(&self, visitor: &mut dyn Visitor) -> Value {
+        visitor.visit_map(self)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+/// `object[index]`, a range as index reads a slice.
+pub struct Index {
+    pub object: Box<dyn Expr>,
+    pub bracket: Token,
+    pub index: Box<dyn Expr>,
+}
+
+impl Index {
+    pub fn new(object: Box<dyn Expr>, bracket: Token, index: Box<dyn Expr>) -> Self {
+        Self { object, bracket, index }
+    }
+}
+
+impl Expr for Index {
+    fn accept(&self, visitor: &mut dyn Visitor) -> Value {
+        visitor.visit_index(self)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+/// `object[index] = value`
+pub struct SetIndex {
+    pub object: Box<dyn Expr>,
+    pub bracket: Token,
+    pub index: Box<dyn Expr>,
+    pub value: Box<dyn Expr>,
+}
+
+impl SetIndex {
+    pub fn new(object: Box<dyn Expr>, bracket: Token, index: Box<dyn Expr>, value: Box<dyn Expr>) -> Self {
+        Self { object, bracket, index, value }
+    }
+}
+
+impl Expr for SetIndex {
+    fn accept(&self, visitor: &mut dyn Visitor) -> Value {
+        visitor.visit_set_index(self)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
 }
 
 
 pub trait Stmt {
     fn accept(&self, visitor: &mut dyn Visitor);
+    fn as_any(&self) -> &dyn Any;
 }
 
 pub struct Expression {
@@ -183,6 +592,9 @@ impl Stmt for Expression {
     fn accept(&self, visitor: &mut dyn Visitor) {
         visitor.visit_expr_stmt(self);
     }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
 }
 
 pub struct Echo {
@@ -198,6 +610,9 @@ impl Stmt for Echo {
     fn accept(&self, visitor: &mut dyn Visitor) {
         visitor.visit_echo_stmt(self);
     }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
 }
 
 pub struct Var {
@@ -230,6 +645,53 @@ impl Stmt for Var {
     fn accept(&self, visitor: &mut dyn Visitor) {
         visitor.visit_var_decl(self);
     }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// What a value is bound to: a plain name or a tuple or array taken apart into further patterns.
+#[derive(Clone, Debug)]
+pub enum VarPattern {
+    Name { name: Token, datatype: Option<Token>, mutable: bool },
+    Tuple { paren: Token, elements: Vec<VarPattern> },
+    Array { bracket: Token, elements: Vec<VarPattern> },
+    // `..pattern`, binds the elements no other pattern took
+    Rest(Box<VarPattern>),
+}
+
+impl VarPattern {
+    /// Every name the pattern binds, in the order they are defined in.
+    pub fn names(&self) -> Vec<&Token> {
+        match self {
+            VarPattern::Name { name, .. } => vec![name],
+            VarPattern::Tuple { elements, .. } | VarPattern::Array { elements, .. } => {
+                elements.iter().flat_map(|element| element.names()).collect()
+            },
+            VarPattern::Rest(pattern) => pattern.names(),
+        }
+    }
+}
+
+/// `set (x, mut y) = pair;` or `set [head, ..rest] = xs;`
+pub struct Destructure {
+    pub pattern: VarPattern,
+    pub expr: Box<dyn Expr>,
+}
+
+impl Destructure {
+    pub fn new(pattern: VarPattern, expr: Box<dyn Expr>) -> Self {
+        Self { pattern, expr }
+    }
+}
+
+impl Stmt for Destructure {
+    fn accept(&self, visitor: &mut dyn Visitor) {
+        visitor.visit_destructure_decl(self);
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
 }
 
 pub struct Block {
@@ -246,6 +708,9 @@ impl Stmt for Block {
     fn accept(&self, visitor: &mut dyn Visitor) {
         visitor.visit_block_stmt(self);
     }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
 }
 
 pub struct If {
@@ -266,16 +731,20 @@ impl Stmt for If {
     fn accept(&self, visitor: &mut dyn Visitor) {
         visitor.visit_if_stmt(self);
     }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
 }
 
 pub struct While {
+    pub label: Option<Token>,
     pub condition: Box<dyn Expr>,
     pub body: Box<dyn Stmt>
 }
 
 impl While {
-    pub fn new(condition: Box<dyn Expr>, body: Box<dyn Stmt>) -> Self {
-        Self { condition, body }
+    pub fn new(label: Option<Token>, condition: Box<dyn Expr>, body: Box<dyn Stmt>) -> Self {
+        Self { label, condition, body }
     }
 }
 
@@ -283,4 +752,346 @@ impl Stmt for While {
     fn accept(&self, visitor: &mut dyn Visitor) {
         visitor.visit_while_stmt(self);
     }
-}
\ No newline at end of file
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// `for (name in iterable) body`, the loop variable is optional.
+pub struct For {
+    pub keyword: Token,
+    pub label: Option<Token>,
+    pub pattern: Option<VarPattern>,
+    pub iterable: Box<dyn Expr>,
+    pub body: Box<dyn Stmt>,
+}
+
+impl For {
+    pub fn new(keyword: Token, label: Option<Token>, pattern: Option<VarPattern>, iterable: Box<dyn Expr>, body: Box<dyn Stmt>) -> Self {
+        Self { keyword, label, pattern, iterable, body }
+    }
+}
+
+impl Stmt for For {
+    fn accept(&self, visitor: &mut dyn Visitor) {
+        visitor.visit_for_stmt(self);
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// `break label?;`, without a label it leaves the innermost loop.
+pub struct Break {
+    pub keyword: Token,
+    pub label: Option<Token>,
+}
+
+impl Break {
+    pub fn new(keyword: Token, label: Option<Token>) -> Self {
+        Self { keyword, label }
+    }
+}
+
+impl Stmt for Break {
+    fn accept(&self, visitor: &mut dyn Visitor) {
+        visitor.visit_break_stmt(self);
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// `continue label?;`, without a label it skips to the next pass of the innermost loop.
+pub struct Continue {
+    pub keyword: Token,
+    pub label: Option<Token>,
+}
+
+impl Continue {
+    pub fn new(keyword: Token, label: Option<Token>) -> Self {
+        Self { keyword, label }
+    }
+}
+
+impl Stmt for Continue {
+    fn accept(&self, visitor: &mut dyn Visitor) {
+        visitor.visit_continue_stmt(self);
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Param {
+    pub datatype: Option<Token>,
+    pub mutability: bool,
+    pub name: Token,
+}
+
+impl Param {
+    pub fn new(datatype: Option<Token>, mutability: bool, name: Token) -> Self {
+        Self { datatype, mutability, name }
+    }
+
+    /// Parameters are bound like any other pattern.
+    pub fn pattern(&self) -> VarPattern {
+        VarPattern::Name { name: self.name.clone(), datatype: self.datatype.clone(), mutable: self.mutability }
+    }
+}
+
+pub struct Function {
+    pub name: Token,
+    pub return_type: Option<Token>,
+    pub params: Vec<Param>,
+    // shared with every function value created from this declaration
+    pub body: Rc<Vec<Box<dyn Stmt>>>,
+    // methods marked `override`, always false outside of entities
+    pub overrides: bool,
+}
+
+impl Function {
+    pub fn new(name: Token, return_type: Option<Token>, params: Vec<Param>, body: Vec<Box<dyn Stmt>>) -> Self {
+        Self { name, return_type, params, body: Rc::new(body), overrides: false }
+    }
+}
+
+impl Stmt for Function {
+    fn accept(&self, visitor: &mut dyn Visitor) {
+        visitor.visit_function_decl(self);
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+pub struct Return {
+    pub keyword: Token,
+    pub value: Option<Box<dyn Expr>>,
+}
+
+impl Return {
+    pub fn new(keyword: Token, value: Option<Box<dyn Expr>>) -> Self {
+        Self { keyword, value }
+    }
+}
+
+impl Stmt for Return {
+    fn accept(&self, visitor: &mut dyn Visitor) {
+        visitor.visit_return_stmt(self);
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+pub struct Throw {
+    pub keyword: Token,
+    pub value: Box<dyn Expr>,
+}
+
+impl Throw {
+    pub fn new(keyword: Token, value: Box<dyn Expr>) -> Self {
+        Self { keyword, value }
+    }
+}
+
+impl Stmt for Throw {
+    fn accept(&self, visitor: &mut dyn Visitor) {
+        visitor.visit_throw_stmt(self);
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// `try { body } catch (name) { handler }`
+pub struct Try {
+    pub body: Vec<Box<dyn Stmt>>,
+    pub name: Token,
+    pub handler: Vec<Box<dyn Stmt>>,
+}
+
+impl Try {
+    pub fn new(body: Vec<Box<dyn Stmt>>, name: Token, handler: Vec<Box<dyn Stmt>>) -> Self {
+        Self { body, name, handler }
+    }
+}
+
+impl Stmt for Try {
+    fn accept(&self, visitor: &mut dyn Visitor) {
+        visitor.visit_try_stmt(self);
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+pub struct Defer {
+    pub keyword: Token,
+    // shared so the interpreter can hold on to it until the enclosing block exits
+    pub statement: Rc<dyn Stmt>,
+}
+
+impl Defer {
+    pub fn new(keyword: Token, statement: Rc<dyn Stmt>) -> Self {
+        Self { keyword, statement }
+    }
+}
+
+impl Stmt for Defer {
+    fn accept(&self, visitor: &mut dyn Visitor) {
+        visitor.visit_defer_stmt(self);
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visibility {
+    Pub,
+    Priv,
+    Prot,
+}
+
+pub struct Entity {
+    pub name: Token,
+    pub open: bool,
+    pub parent: Option<Variable>,
+    pub traits: Vec<Variable>,
+    // shared with the runtime entity, initializers run on every `new`
+    pub fields: Rc<Vec<Var>>,
+    pub methods: Vec<Function>,
+    // visibility of every field and method declared in this entity
+    pub visibility: HashMap<String, Visibility>,
+}
+
+impl Entity {
+    pub fn new(name: Token, open: bool, parent: Option<Variable>, traits: Vec<Variable>, fields: Vec<Var>, methods: Vec<Function>, visibility: HashMap<String, Visibility>) -> Self {
+        Self { name, open, parent, traits, fields: Rc::new(fields), methods, visibility }
+    }
+}
+
+impl Stmt for Entity {
+    fn accept(&self, visitor: &mut dyn Visitor) {
+        visitor.visit_entity_decl(self);
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+pub struct Trait {
+    pub name: Token,
+    // signatures only, their bodies are empty
+    pub required: Vec<Function>,
+    pub defaults: Vec<Function>,
+}
+
+impl Trait {
+    pub fn new(name: Token, required: Vec<Function>, defaults: Vec<Function>) -> Self {
+        Self { name, required, defaults }
+    }
+}
+
+impl Stmt for Trait {
+    fn accept(&self, visitor: &mut dyn Visitor) {
+        visitor.visit_trait_decl(self);
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// A top-level declaration marked `pub`, visible to modules importing this one.
+pub struct Export {
+    pub name: Token,
+    pub declaration: Box<dyn Stmt>,
+}
+
+impl Export {
+    pub fn new(name: Token, declaration: Box<dyn Stmt>) -> Self {
+        Self { name, declaration }
+    }
+}
+
+impl Stmt for Export {
+    fn accept(&self, visitor: &mut dyn Visitor) {
+        visitor.visit_export_stmt(self);
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+pub struct EnumVariant {
+    pub name: Token,
+    pub fields: Vec<Param>,
+}
+
+impl EnumVariant {
+    pub fn new(name: Token, fields: Vec<Param>) -> Self {
+        Self { name, fields }
+    }
+}
+
+pub struct Enum {
+    pub name: Token,
+    pub variants: Rc<Vec<EnumVariant>>,
+}
+
+impl Enum {
+    pub fn new(name: Token, variants: Vec<EnumVariant>) -> Self {
+        Self { name, variants: Rc::new(variants) }
+    }
+}
+
+impl Stmt for Enum {
+    fn accept(&self, visitor: &mut dyn Visitor) {
+        visitor.visit_enum_decl(self);
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+pub enum Pattern {
+    /// `Enum.Variant` or `Enum.Variant(a, b)`, binding the payload positionally.
+    Variant { enum_name: Variable, variant: Token, bindings: Vec<Token> },
+    /// `_`, matches anything.
+    Wildcard(Token),
+}
+
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub body: Box<dyn Stmt>,
+}
+
+impl MatchArm {
+    pub fn new(pattern: Pattern, body: Box<dyn Stmt>) -> Self {
+        Self { pattern, body }
+    }
+}
+
+pub struct Match {
+    pub keyword: Token,
+    pub subject: Box<dyn Expr>,
+    pub arms: Vec<MatchArm>,
+}
+
+impl Match {
+    pub fn new(keyword: Token, subject: Box<dyn Expr>, arms: Vec<MatchArm>) -> Self {
+        Self { keyword, subject, arms }
+    }
+}
+
+impl Stmt for Match {
+    fn accept(&self, visitor: &mut dyn Visitor) {
+        visitor.visit_match_stmt(self);
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
@@ -1,105 +1,421 @@
-// use crate::util::Object;
-/*use crate::util::Value;
+// prints the syntax tree as parenthesized prefix expressions, mostly useful
+// to check what the parser made of a piece of source
 
-use super::ast::{Expr, Visitor};
+use crate::util::Value;
 
+use super::ast::{Expr, Function, If, Param, Pattern, Stmt, Var, VarPattern, Visitor};
 
-#[deprecated]
-pub struct AstPrinter();
+#[derive(Default)]
+pub struct AstPrinter {
+    // text of the statement visited last, statements don't return a value
+    output: String,
+}
 
 impl AstPrinter {
-    pub fn print(&mut self, expr: &dyn Expr) -> Value {
-        return expr.accept(self)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// One line per top-level statement.
+    pub fn print(&mut self, stmts: &[Box<dyn Stmt>]) -> String {
+        stmts.iter().map(|stmt| self.stmt(&**stmt)).collect::<Vec<String>>().join("\n")
+    }
+
+    pub fn print_expr(&mut self, expr: &dyn Expr) -> String {
+        match expr.accept(self) {
+            Value::String(text) => text,
+            value => value.to_string(),
+        }
+    }
+
+    fn stmt(&mut self, stmt: &dyn Stmt) -> String {
+        stmt.accept(self);
+        std::mem::take(&mut self.output)
+    }
+
+    fn stmts(&mut self, stmts: &[Box<dyn Stmt>]) -> Vec<String> {
+        stmts.iter().map(|stmt| self.stmt(&**stmt)).collect()
     }
 
-    fn parenthesize(&self, name: String, exprs: &[&dyn Expr]) -> String {
+    fn exprs(&mut self, exprs: &[Box<dyn Expr>]) -> Vec<String> {
+        exprs.iter().map(|expr| self.print_expr(&**expr)).collect()
+    }
+
+    fn parenthesize(&self, name: &str, parts: Vec<String>) -> String {
         let mut builder: String = String::new();
 
         builder.push('(');
-        builder.push_str(&name);
-
-        for expr in exprs.to_vec() {
+        builder.push_str(name);
+        for part in parts {
             builder.push(' ');
-            //builder.push_str(&expr.accept(&mut self).to_string());
+            builder.push_str(&part);
         }
-        
         builder.push(')');
 
         builder
     }
+
+    fn var(&mut self, var: &Var) -> String {
+        let mut parts: Vec<String> = Vec::new();
+        if var.mutability {
+            parts.push("mut".to_string());
+        }
+        parts.push(match &var.datatype {
+            Some(datatype) => format!("{}: {}", var.name.lexeme, datatype.lexeme),
+            None => var.name.lexeme.clone(),
+        });
+        if let Some(expr) = &var.expr {
+            parts.push(self.print_expr(&**expr));
+        }
+        self.parenthesize("set", parts)
+    }
+
+    fn param(&self, param: &Param) -> String {
+        self.var_pattern(&param.pattern())
+    }
+
+    fn function(&mut self, function: &Function) -> String {
+        let params: Vec<String> = function.params.iter().map(|param| self.param(param)).collect();
+        let mut parts: Vec<String> = vec![function.name.lexeme.clone(), format!("({})", params.join(" "))];
+        if let Some(return_type) = &function.return_type {
+            parts.push(format!("-> {}", return_type.lexeme));
+        }
+        parts.extend(self.stmts(&function.body));
+        self.parenthesize(if function.overrides { "override fun" } else { "fun" }, parts)
+    }
+
+    fn var_pattern(&self, pattern: &VarPattern) -> String {
+        let elements = |elements: &Vec<VarPattern>| elements.iter().map(|element| self.var_pattern(element)).collect::<Vec<String>>().join(", ");
+        match pattern {
+            VarPattern::Name { name, datatype, mutable } => {
+                let name: String = match datatype {
+                    Some(datatype) => format!("{}: {}", name.lexeme, datatype.lexeme),
+                    None => name.lexeme.clone(),
+                };
+                match mutable {
+                    true => format!("mut {name}"),
+                    false => name,
+                }
+            },
+            VarPattern::Tuple { elements: patterns, .. } => format!("({})", elements(patterns)),
+            VarPattern::Array { elements: patterns, .. } => format!("[{}]", elements(patterns)),
+            VarPattern::Rest(pattern) => format!("..{}", self.var_pattern(pattern)),
+        }
+    }
 }
 
 impl Visitor for AstPrinter {
-    fn visit_binary(&self, binary: &super::ast::Binary) -> Value {
-        Value::String(self.parenthesize(binary.operator.lexeme.clone(), &[&*binary.left, &*binary.right]))
+    fn visit_binary(&mut self, binary: &super::ast::Binary) -> Value {
+        let parts: Vec<String> = vec![self.print_expr(&*binary.left), self.print_expr(&*binary.right)];
+        Value::String(self.parenthesize(&binary.operator.lexeme, parts))
     }
 
-    fn visit_grouping(&self, grouping: &super::ast::Grouping) -> Value {
-        Value::String(self.parenthesize("group".to_string(), &[&*grouping.expression]))
+    fn visit_grouping(&mut self, grouping: &super::ast::Grouping) -> Value {
+        let parts: Vec<String> = vec![self.print_expr(&*grouping.expression)];
+        Value::String(self.parenthesize("group", parts))
     }
 
-    fn visit_literal(&self, literal: & super::ast::Literal) -> Value {
-        return match &literal.value {
-            Some(value) => value.clone(),
-            None => Value::Null, 
+    fn visit_literal(&self, literal: &super::ast::Literal) -> Value {
+        match &literal.value {
+            Some(Value::String(text)) => Value::String(format!("{text:?}")),
+            Some(value) => Value::String(value.to_string()),
+            None => Value::String("null".to_string()),
         }
     }
 
-    fn visit_unary(&self, unary: &super::ast::Unary) -> Value {
-        Value::String(self.parenthesize(unary.operator.lexeme.clone(), &[&*unary.right]))
+    fn visit_interpolation(&mut self, interpolation: &super::ast::Interpolation) -> Value {
+        let parts: Vec<String> = self.exprs(&interpolation.parts);
+        Value::String(self.parenthesize("interpolate", parts))
     }
 
-    fn visit_echo_stmt(&self, echo: &super::ast::Echo) {
-        todo!()
+    fn visit_unary(&mut self, unary: &super::ast::Unary) -> Value {
+        let parts: Vec<String> = vec![self.print_expr(&*unary.right)];
+        Value::String(self.parenthesize(&unary.operator.lexeme, parts))
     }
-    
-    fn visit_expr_stmt(&self, expr: &super::ast::Expression) {
-        todo!()
+
+    fn visit_cast(&mut self, cast: &super::ast::Cast) -> Value {
+        let parts: Vec<String> = vec![self.print_expr(&*cast.value), cast.type_.lexeme.clone()];
+        Value::String(self.parenthesize("as", parts))
     }
-    
-    fn visit_var_decl(&mut self, var: &super::ast::Var) {
-        todo!()
+
+    fn visit_logical(&mut self, logical: &super::ast::Logical) -> Value {
+        let parts: Vec<String> = vec![self.print_expr(&*logical.lhs), self.print_expr(&*logical.rhs)];
+        Value::String(self.parenthesize(&logical.op.lexeme, parts))
     }
 
-    fn visit_variable(&self, variable: &super::ast::Variable) -> Value {
-        todo!()
+    fn visit_variable(&mut self, variable: &super::ast::Variable) -> Value {
+        Value::String(variable.name.lexeme.clone())
     }
 
     fn visit_assign(&mut self, assign: &super::ast::Assign) -> Value {
-        todo!()
+        let parts: Vec<String> = vec![assign.name.lexeme.clone(), self.print_expr(&*assign.value)];
+        Value::String(self.parenthesize("=", parts))
     }
-}
 
-#[test]
-fn test_printer() {
-    use super::ast::{Literal, Binary, Unary, Grouping};
-    use crate::lexer::tokens::{Token, TokenType};
+    fn visit_call(&mut self, call: &super::ast::Call) -> Value {
+        let mut parts: Vec<String> = vec![self.print_expr(&*call.callee)];
+        parts.extend(self.exprs(&call.arguments));
+        Value::String(self.parenthesize("call", parts))
+    }
+
+    fn visit_get(&mut self, get: &super::ast::Get) -> Value {
+        let parts: Vec<String> = vec![self.print_expr(&*get.object), get.name.lexeme.clone()];
+        Value::String(self.parenthesize(".", parts))
+    }
+
+    fn visit_set(&mut self, set: &super::ast::Set) -> Value {
+        let target: Vec<String> = vec![self.print_expr(&*set.object), set.name.lexeme.clone()];
+        let target: String = self.parenthesize(".", target);
+        let parts: Vec<String> = vec![target, self.print_expr(&*set.value)];
+        Value::String(self.parenthesize("=", parts))
+    }
+
+    fn visit_this(&mut self, this: &super::ast::This) -> Value {
+        Value::String(this.keyword.lexeme.clone())
+    }
+
+    fn visit_new(&mut self, new: &super::ast::New) -> Value {
+        let mut parts: Vec<String> = vec![self.print_expr(&*new.entity)];
+        parts.extend(self.exprs(&new.arguments));
+        Value::String(self.parenthesize("new", parts))
+    }
+
+    fn visit_parent(&mut self, parent: &super::ast::Parent) -> Value {
+        let parts: Vec<String> = vec![parent.keyword.lexeme.clone(), parent.method.lexeme.clone()];
+        Value::String(self.parenthesize(".", parts))
+    }
+
+    fn visit_range(&mut self, range: &super::ast::Range) -> Value {
+        let mut parts: Vec<String> = vec![self.print_expr(&*range.start), self.print_expr(&*range.end)];
+        if let Some(step) = &range.step {
+            parts.push(self.print_expr(&**step));
+        }
+        Value::String(self.parenthesize(&range.operator.lexeme, parts))
+    }
+
+    fn visit_array(&mut self, array: &super::ast::Array) -> Value {
+        let parts: Vec<String> = self.exprs(&array.elements);
+        Value::String(self.parenthesize("array", parts))
+    }
+
+    fn visit_map(&mut self, map: &super::ast::Map) -> Value {
+        let parts: Vec<String> = map.entries.iter()
+            .map(|(key, value)| format!("({} {})", self.print_expr(&**key), self.print_expr(&**value)))
+            .collect();
+        Value::String(self.parenthesize("map", parts))
+    }
+
+    fn visit_tuple(&mut self, tuple: &super::ast::Tuple) -> Value {
+        let parts: Vec<String> = self.exprs(&tuple.elements);
+        Value::String(self.parenthesize("tuple", parts))
+    }
+
+    fn visit_index(&mut self, index: &super::ast::Index) -> Value {
+        let parts: Vec<String> = vec![self.print_expr(&*index.object), self.print_expr(&*index.index)];
+        Value::String(self.parenthesize("index", parts))
+    }
+
+    fn visit_set_index(&mut self, set_index: &super::ast::SetIndex) -> Value {
+        let target: Vec<String> = vec![self.print_expr(&*set_index.object), self.print_expr(&*set_index.index)];
+        let target: String = self.parenthesize("index", target);
+        let parts: Vec<String> = vec![target, self.print_expr(&*set_index.value)];
+        Value::String(self.parenthesize("=", parts))
+    }
+
+    fn visit_expr_stmt(&mut self, expr: &super::ast::Expression) {
+        let parts: Vec<String> = vec![self.print_expr(&*expr.expr)];
+        self.output = self.parenthesize(";", parts);
+    }
 
-    // Create the literal expressions.
-    let literal_expr_1: Literal = Literal::new(Some(Value::Integer(123)));
-    let literal_expr_2: Literal = Literal::new(Some(Value::Float(45.67)));
+    fn visit_echo_stmt(&mut self, echo: &super::ast::Echo) {
+        let parts: Vec<String> = vec![self.print_expr(&*echo.expr)];
+        self.output = self.parenthesize("echo", parts);
+    }
+
+    fn visit_block_stmt(&mut self, block: &super::ast::Block) {
+        let parts: Vec<String> = self.stmts(&block.statements);
+        self.output = self.parenthesize("block", parts);
+    }
+
+    fn visit_if_stmt(&mut self, if_: &If) {
+        let mut parts: Vec<String> = vec![self.print_expr(&*if_.condition), self.stmt(&*if_.then_branch)];
+
+        // the parser turns `elif` into an `else` branch holding another `if`, print the chain flat again
+        let mut else_branch: Option<&dyn Stmt> = if_.else_branch.as_deref();
+        while let Some(branch) = else_branch {
+            match branch.as_any().downcast_ref::<If>() {
+                Some(elif) => {
+                    let clause: Vec<String> = vec![self.print_expr(&*elif.condition), self.stmt(&*elif.then_branch)];
+                    parts.push(self.parenthesize("elif", clause));
+                    else_branch = elif.else_branch.as_deref();
+                },
+                None => {
+                    let clause: Vec<String> = vec![self.stmt(branch)];
+                    parts.push(self.parenthesize("else", clause));
+                    else_branch = None;
+                },
+            }
+        }
+
+        self.output = self.parenthesize("if", parts);
+    }
+
+    fn visit_while_stmt(&mut self, while_: &super::ast::While) {
+        let mut parts: Vec<String> = Vec::new();
+        if let Some(label) = &while_.label {
+            parts.push(format!("{}:", label.lexeme));
+        }
+        parts.push(self.print_expr(&*while_.condition));
+        parts.push(self.stmt(&*while_.body));
+        self.output = self.parenthesize("while", parts);
+    }
+
+    fn visit_for_stmt(&mut self, for_: &super::ast::For) {
+        let mut parts: Vec<String> = Vec::new();
+        if let Some(label) = &for_.label {
+            parts.push(format!("{}:", label.lexeme));
+        }
+        if let Some(pattern) = &for_.pattern {
+            parts.push(self.var_pattern(pattern));
+        }
+        parts.push(self.print_expr(&*for_.iterable));
+        parts.push(self.stmt(&*for_.body));
+        self.output = self.parenthesize("for", parts);
+    }
+
+    fn visit_break_stmt(&mut self, break_: &super::ast::Break) {
+        let parts: Vec<String> = break_.label.iter().map(|label| label.lexeme.clone()).collect();
+        self.output = self.parenthesize("break", parts);
+    }
+
+    fn visit_continue_stmt(&mut self, continue_: &super::ast::Continue) {
+        let parts: Vec<String> = continue_.label.iter().map(|label| label.lexeme.clone()).collect();
+        self.output = self.parenthesize("continue", parts);
+    }
+
+    fn visit_return_stmt(&mut self, return_: &super::ast::Return) {
+        let parts: Vec<String> = return_.value.iter().map(|value| self.print_expr(&**value)).collect();
+        self.output = self.parenthesize("return", parts);
+    }
+
+    fn visit_match_stmt(&mut self, match_: &super::ast::Match) {
+        let mut parts: Vec<String> = vec![self.print_expr(&*match_.subject)];
+        for arm in &match_.arms {
+            let pattern: String = match &arm.pattern {
+                Pattern::Variant { enum_name, variant, bindings } if bindings.is_empty() => format!("{}.{}", enum_name.name.lexeme, variant.lexeme),
+                Pattern::Variant { enum_name, variant, bindings } => {
+                    let bindings: Vec<&str> = bindings.iter().map(|binding| binding.lexeme.as_str()).collect();
+                    format!("{}.{}({})", enum_name.name.lexeme, variant.lexeme, bindings.join(", "))
+                },
+                Pattern::Wildcard(token) => token.lexeme.clone(),
+            };
+            let clause: Vec<String> = vec![pattern, self.stmt(&*arm.body)];
+            parts.push(self.parenthesize("->", clause));
+        }
+        self.output = self.parenthesize("match", parts);
+    }
+
+    fn visit_throw_stmt(&mut self, throw: &super::ast::Throw) {
+        let parts: Vec<String> = vec![self.print_expr(&*throw.value)];
+        self.output = self.parenthesize("throw", parts);
+    }
+
+    fn visit_try_stmt(&mut self, try_: &super::ast::Try) {
+        let body: Vec<String> = self.stmts(&try_.body);
+        let body: String = self.parenthesize("block", body);
+        let mut handler: Vec<String> = vec![try_.name.lexeme.clone()];
+        handler.extend(self.stmts(&try_.handler));
+        let parts: Vec<String> = vec![body, self.parenthesize("catch", handler)];
+        self.output = self.parenthesize("try", parts);
+    }
 
-    // Create the unary expression that references the first literal.
-    let unary_expr: Unary = Unary::new(
-        Token::new(TokenType::Minus, "-".to_string(), None, 1),
-        Box::new(literal_expr_1),
-    );
+    fn visit_defer_stmt(&mut self, defer: &super::ast::Defer) {
+        let parts: Vec<String> = vec![self.stmt(&*defer.statement)];
+        self.output = self.parenthesize("defer", parts);
+    }
+
+    fn visit_var_decl(&mut self, var: &Var) {
+        self.output = self.var(var);
+    }
+
+    fn visit_destructure_decl(&mut self, destructure: &super::ast::Destructure) {
+        let parts: Vec<String> = vec![self.var_pattern(&destructure.pattern), self.print_expr(&*destructure.expr)];
+        self.output = self.parenthesize("set", parts);
+    }
+
+    fn visit_function_decl(&mut self, function: &Function) {
+        self.output = self.function(function);
+    }
+
+    fn visit_entity_decl(&mut self, entity: &super::ast::Entity) {
+        let mut parts: Vec<String> = vec![entity.name.lexeme.clone()];
+        if let Some(parent) = &entity.parent {
+            parts.push(format!("< {}", parent.name.lexeme));
+        }
+        for trait_ in &entity.traits {
+            parts.push(format!(": {}", trait_.name.lexeme));
+        }
+        for field in entity.fields.iter() {
+            parts.push(self.var(field));
+        }
+        for method in &entity.methods {
+            parts.push(self.function(method));
+        }
+        self.output = self.parenthesize(if entity.open { "open entity" } else { "entity" }, parts);
+    }
+
+    fn visit_trait_decl(&mut self, trait_: &super::ast::Trait) {
+        let mut parts: Vec<String> = vec![trait_.name.lexeme.clone()];
+        for method in trait_.required.iter().chain(&trait_.defaults) {
+            parts.push(self.function(method));
+        }
+        self.output = self.parenthesize("trait", parts);
+    }
+
+    fn visit_enum_decl(&mut self, enum_: &super::ast::Enum) {
+        let mut parts: Vec<String> = vec![enum_.name.lexeme.clone()];
+        for variant in enum_.variants.iter() {
+            let fields: Vec<String> = variant.fields.iter().map(|field| self.param(field)).collect();
+            parts.push(match fields.is_empty() {
+                true => variant.name.lexeme.clone(),
+                false => format!("{}({})", variant.name.lexeme, fields.join(", ")),
+            });
+        }
+        self.output = self.parenthesize("enum", parts);
+    }
+
+    fn visit_export_stmt(&mut self, export: &super::ast::Export) {
+        let parts: Vec<String> = vec![self.stmt(&*export.declaration)];
+        self.output = self.parenthesize("export", parts);
+    }
+}
 
-    // Create the grouping expression that references the second literal.
-    let grouping_expr: Grouping = Grouping::new(Box::new(literal_expr_2));
+#[cfg(test)]
+fn print_source(source: &str) -> String {
+    use crate::lexer::{tokens::Token, Lexer};
+    use super::Parser;
 
-    // Create the binary expression that references the unary and grouping expressions.
-    let expr: Box<Binary> = Box::new(Binary::new(
-        Box::new(unary_expr),
-        Token::new(TokenType::Star, "*".to_string(), None, 1),
-        Box::new(grouping_expr),
-    ));
+    let tokens: Vec<Token> = Lexer::new(source.to_string()).tokenize();
+    let stmts: Vec<Box<dyn Stmt>> = Parser::new(tokens).parse();
+    AstPrinter::new().print(&stmts)
+}
 
-    // Instantiate the AST printer.
-    let printer: AstPrinter = AstPrinter();
+#[test]
+fn test_printer() {
+    assert_eq!(print_source("echo -123 * (45.67);"), "(echo (* (- 123) (group 45.67)))");
+    assert_eq!(print_source("i32 mut x = 1;"), "(set mut x: i32 1)");
+}
 
-    //let result: Value = printer.print(&*expr);
+#[test]
+fn test_print_elif_chain() {
+    let printed: String = print_source("
+        if (x < 0) echo \"negative\";
+        elif (x == 0) echo \"zero\";
+        elif (x < 10) { echo \"small\"; }
+        else echo \"large\";
+    ");
 
-    // Print the expression.
-    //println!("{}", result); // Expected output: (* (- 123) (group 45.67))
-}*/
\ No newline at end of file
+    assert_eq!(printed, "(if (< x 0) (echo \"negative\") (elif (== x 0) (echo \"zero\")) (elif (< x 10) (block (echo \"small\"))) (else (echo \"large\")))");
+    // an `if` nested in a block under `else` is not part of the chain
+    assert_eq!(print_source("if (a) echo 1; else { if (b) echo 2; }"), "(if a (echo 1) (else (block (if b (echo 2)))))");
+}
@@ -1,7 +1,10 @@
 
-use ast::{Assign, Binary, Block, Echo, Expr, Expression, Grouping, If, Literal, Logical, Stmt, Unary, Var, Variable, While};
+use std::collections::HashMap;
 
-use crate::lexer::tokens::{Token, TokenType};
+use ast::{Array, Assign, Binary, Block, Break, Call, Cast, Continue, Defer, Destructure, Echo, Entity, Enum, EnumVariant, Export, Expr, Expression, For, Function, Get, Grouping, If, Index, Interpolation, Literal, Logical, Map, Match, MatchArm, New, Param, Parent, Pattern, Range, Return, Set, SetIndex, Stmt, This, Throw, Trait, Try, Tuple, Unary, Var, VarPattern, Variable, Visibility, While};
+
+use crate::interpreter::numeric::NumericType;
+use crate::lexer::{self, tokens::{Token, TokenType}};
 use crate::util::error_formatter::{ErrorHandler, ErrorKind};
 use crate::util::Value;
 
@@ -18,9 +21,16 @@ fn error(token: Token, msg: &'static str) -> ! {
 pub mod ast;
 pub mod ast_printer;
 
+enum NamedDecl {
+    Var(Var),
+    Function(Function),
+}
+
 pub struct Parser {
     tokens: Vec<Token>,
     current: usize,
+    // labels of the loops around the statement being parsed, innermost last
+    loops: Vec<Option<String>>,
 }
 
 impl Parser {
@@ -28,18 +38,65 @@ impl Parser {
         Self {
             tokens,
             current: 0,
+            loops: Vec::new(),
         }
     }
 
     pub fn parse(&mut self) -> Vec<Box<dyn Stmt>> {
         let mut stmts: Vec<Box<dyn Stmt>> = Vec::new();
         while !self.eof() {
-            stmts.push(self.declaration());
+            stmts.push(self.top_level_declaration());
         }
 
         return stmts;
     }
 
+    fn top_level_declaration(&mut self) -> Box<dyn Stmt> {
+        if self.expect(&[TokenType::Pub]) {
+            let Some(name) = self.declared_name() else {
+                error(self.peek(), "Expect declaration after 'pub'.");
+            };
+            return Box::new(Export::new(name, self.declaration()));
+        }
+        if self.check(TokenType::Prot) {
+            error(self.peek(), "'prot' is only allowed on entity members.");
+        }
+        // top-level declarations are private to their module by default
+        self.expect(&[TokenType::Priv]);
+
+        return self.declaration();
+    }
+
+    /// Looks ahead for the name introduced by the declaration starting at the current token.
+    fn declared_name(&self) -> Option<Token> {
+        let is_typed = |index: usize| -> bool {
+            self.tokens[index].type_ == TokenType::Identifier
+                && (self.tokens[index + 1].type_ == TokenType::Identifier || self.tokens[index + 1].type_ == TokenType::Mut)
+        };
+
+        let mut index: usize = self.current;
+        match self.tokens[index].type_ {
+            TokenType::Open | TokenType::Entity | TokenType::Trait | TokenType::Enum | TokenType::Set | TokenType::Void => {},
+            _ if is_typed(index) => {},
+            _ => return None,
+        }
+
+        while matches!(self.tokens[index].type_, TokenType::Open | TokenType::Entity | TokenType::Trait | TokenType::Enum | TokenType::Set | TokenType::Void | TokenType::Mut) {
+            index += 1;
+        }
+        if is_typed(index) {
+            index += 1;
+            if self.tokens[index].type_ == TokenType::Mut {
+                index += 1;
+            }
+        }
+
+        match self.tokens[index].type_ {
+            TokenType::Identifier => Some(self.tokens[index].clone()),
+            _ => None,
+        }
+    }
+
     fn expect(&mut self, types: &[TokenType]) -> bool {
         for type_ in types {
             if self.check(*type_) {
@@ -78,6 +135,13 @@ impl Parser {
         self.tokens.get(self.current).unwrap().clone()
     }
 
+    fn peek_next(&self) -> Token {
+        if self.eof() {
+            return self.peek();
+        }
+        self.tokens.get(self.current + 1).unwrap().clone()
+    }
+
     fn back(&mut self) -> Token {
         self.tokens.get(self.current - 1).unwrap().clone()
     }
@@ -90,34 +154,247 @@ impl Parser {
     }
 
     fn declaration(&mut self) -> Box<dyn Stmt> {
+        if self.check(TokenType::Pub) || self.check(TokenType::Priv) || self.check(TokenType::Prot) {
+            error(self.peek(), "Visibility modifiers are only allowed on top-level declarations and entity members.");
+        }
+        if self.expect(&[TokenType::Open]) {
+            self.consume(TokenType::Entity, "Expect 'entity' after 'open'.");
+            return self.entity_declaration(true);
+        }
+        if self.expect(&[TokenType::Entity]) {
+            return self.entity_declaration(false);
+        }
+        if self.expect(&[TokenType::Trait]) {
+            return self.trait_declaration();
+        }
+        if self.expect(&[TokenType::Enum]) {
+            return self.enum_declaration();
+        }
+        if self.check(TokenType::Set) && matches!(self.peek_next().type_, TokenType::LParen | TokenType::LSquare) {
+            self.next();
+            return self.destructure_declaration();
+        }
+
+        return match self.named_declaration() {
+            Some(NamedDecl::Var(var)) => Box::new(var),
+            Some(NamedDecl::Function(function)) => Box::new(function),
+            None => self.statement(),
+        };
+    }
+
+    /// Variable and function declarations, introduced by `set`, `void` or a type name.
+    /// Shared by top-level code, blocks and entity bodies.
+    fn named_declaration(&mut self) -> Option<NamedDecl> {
         if self.expect(&[TokenType::Set]) {
+            if self.check(TokenType::Identifier) && self.peek_next().type_ == TokenType::LParen {
+                return Some(NamedDecl::Function(self.function(None)));
+            }
             if self.peek().type_ == TokenType::Mut {
-                return self.var_declaration(true, true);
+                return Some(NamedDecl::Var(self.var_declaration(true, true)));
             }
-            return self.var_declaration(false, true);
+            return Some(NamedDecl::Var(self.var_declaration(false, true)));
         }
-        if self.expect(&[TokenType::Identifier]) && (self.peek().type_ == TokenType::Identifier || self.peek().type_ == TokenType::Mut) {
+        if self.expect(&[TokenType::Void]) {
+            let return_type: Token = self.back();
+            return Some(NamedDecl::Function(self.function(Some(return_type))));
+        }
+        if self.check(TokenType::Identifier) && (self.peek_next().type_ == TokenType::Identifier || self.peek_next().type_ == TokenType::Mut) {
+            let datatype: Token = self.next();
+            if self.check(TokenType::Identifier) && self.peek_next().type_ == TokenType::LParen {
+                return Some(NamedDecl::Function(self.function(Some(datatype))));
+            }
             if self.peek().type_ == TokenType::Mut {
-                return self.var_declaration(true, false);
+                return Some(NamedDecl::Var(self.var_declaration(true, false)));
+            }
+            return Some(NamedDecl::Var(self.var_declaration(false, false)));
+        }
+
+        None
+    }
+
+    fn entity_declaration(&mut self, open: bool) -> Box<dyn Stmt> {
+        let name: Token = self.consume(TokenType::Identifier, "Expect entity name.");
+
+        let mut parent: Option<Variable> = None;
+        if self.expect(&[TokenType::Colon]) {
+            let parent_name: Token = self.consume(TokenType::Identifier, "Expect parent entity name after ':'.");
+            parent = Some(Variable::new(parent_name));
+        }
+
+        let mut traits: Vec<Variable> = Vec::new();
+        if self.expect(&[TokenType::With]) {
+            loop {
+                let trait_name: Token = self.consume(TokenType::Identifier, "Expect trait name after 'with'.");
+                traits.push(Variable::new(trait_name));
+                if !self.expect(&[TokenType::Comma]) {
+                    break;
+                }
+            }
+        }
+
+        self.consume(TokenType::LBrace, "Expect '{' before entity body.");
+
+        let mut fields: Vec<Var> = Vec::new();
+        let mut methods: Vec<Function> = Vec::new();
+        let mut visibility: HashMap<String, Visibility> = HashMap::new();
+
+        while !self.check(TokenType::RBrace) && !self.eof() {
+            if self.check(TokenType::Init) {
+                let init: Token = self.next();
+                methods.push(self.function_body(init, None));
+                continue;
+            }
+
+            // members are public unless stated otherwise
+            let member_visibility: Visibility = if self.expect(&[TokenType::Priv]) {
+                Visibility::Priv
+            } else if self.expect(&[TokenType::Prot]) {
+                Visibility::Prot
+            } else {
+                self.expect(&[TokenType::Pub]);
+                Visibility::Pub
+            };
+
+            let overrides: bool = self.expect(&[TokenType::Override]);
+
+            match self.named_declaration() {
+                Some(NamedDecl::Var(var)) if overrides => error(var.name, "Only methods can be overridden."),
+                Some(NamedDecl::Var(var)) => {
+                    visibility.insert(var.name.lexeme.clone(), member_visibility);
+                    fields.push(var);
+                },
+                Some(NamedDecl::Function(mut function)) => {
+                    visibility.insert(function.name.lexeme.clone(), member_visibility);
+                    function.overrides = overrides;
+                    methods.push(function);
+                },
+                None => error(self.peek(), "Expect field or method declaration."),
+            }
+        }
+
+        self.consume(TokenType::RBrace, "Expect '}' after entity body.");
+        return Box::new(Entity::new(name, open, parent, traits, fields, methods, visibility));
+    }
+
+    fn enum_declaration(&mut self) -> Box<dyn Stmt> {
+        let name: Token = self.consume(TokenType::Identifier, "Expect enum name.");
+        self.consume(TokenType::LBrace, "Expect '{' before enum variants.");
+
+        let mut variants: Vec<EnumVariant> = Vec::new();
+        while !self.check(TokenType::RBrace) && !self.eof() {
+            let variant: Token = self.consume(TokenType::Identifier, "Expect variant name.");
+
+            let mut fields: Vec<Param> = Vec::new();
+            if self.check(TokenType::LParen) {
+                fields = self.parameters();
+            }
+            variants.push(EnumVariant::new(variant, fields));
+
+            if !self.expect(&[TokenType::Comma]) {
+                break;
+            }
+        }
+
+        self.consume(TokenType::RBrace, "Expect '}' after enum variants.");
+        return Box::new(Enum::new(name, variants));
+    }
+
+    fn function(&mut self, return_type: Option<Token>) -> Function {
+        let name: Token = self.consume(TokenType::Identifier, "Expect function name.");
+        self.function_body(name, return_type)
+    }
+
+    fn function_body(&mut self, name: Token, return_type: Option<Token>) -> Function {
+        let params: Vec<Param> = self.parameters();
+
+        self.consume(TokenType::LBrace, "Expect '{' before function body.");
+        let body: Vec<Box<dyn Stmt>> = self.function_block();
+
+        return Function::new(name, return_type, params, body);
+    }
+
+    /// Parses a function body, `break` and `continue` inside it cannot reach loops around the function.
+    fn function_block(&mut self) -> Vec<Box<dyn Stmt>> {
+        let loops: Vec<Option<String>> = std::mem::take(&mut self.loops);
+        let body: Vec<Box<dyn Stmt>> = self.block();
+        self.loops = loops;
+        body
+    }
+
+    fn parameters(&mut self) -> Vec<Param> {
+        self.consume(TokenType::LParen, "Expect '(' after function name.");
+
+        let mut params: Vec<Param> = Vec::new();
+        if !self.check(TokenType::RParen) {
+            loop {
+                params.push(self.parameter());
+                if !self.expect(&[TokenType::Comma]) {
+                    break;
+                }
             }
-            return self.var_declaration(false, false);
         }
+        self.consume(TokenType::RParen, "Expect ')' after parameters.");
 
-        return self.statement();
+        params
     }
 
-    fn var_declaration(&mut self, mutable: bool, inferred: bool) -> Box<dyn Stmt> {
+    fn trait_declaration(&mut self) -> Box<dyn Stmt> {
+        let name: Token = self.consume(TokenType::Identifier, "Expect trait name.");
+        self.consume(TokenType::LBrace, "Expect '{' before trait body.");
+
+        let mut required: Vec<Function> = Vec::new();
+        let mut defaults: Vec<Function> = Vec::new();
+
+        while !self.check(TokenType::RBrace) && !self.eof() {
+            let return_type: Option<Token> = if self.expect(&[TokenType::Set]) {
+                None
+            } else if self.expect(&[TokenType::Void]) {
+                Some(self.back())
+            } else if self.check(TokenType::Identifier) && self.peek_next().type_ == TokenType::Identifier {
+                Some(self.next())
+            } else {
+                error(self.peek(), "Expect method declaration.");
+            };
+
+            let method: Token = self.consume(TokenType::Identifier, "Expect method name.");
+            let params: Vec<Param> = self.parameters();
+
+            // a signature without a body has to be provided by every implementing entity
+            if self.expect(&[TokenType::Semicolon]) {
+                required.push(Function::new(method, return_type, params, Vec::new()));
+            } else {
+                self.consume(TokenType::LBrace, "Expect '{' or ';' after method signature.");
+                let body: Vec<Box<dyn Stmt>> = self.function_block();
+                defaults.push(Function::new(method, return_type, params, body));
+            }
+        }
+
+        self.consume(TokenType::RBrace, "Expect '}' after trait body.");
+        return Box::new(Trait::new(name, required, defaults));
+    }
+
+    fn parameter(&mut self) -> Param {
+        // param -> IDENTIFIER? "mut"? IDENTIFIER ;
+        let mut datatype: Option<Token> = None;
+        if self.check(TokenType::Identifier) && (self.peek_next().type_ == TokenType::Identifier || self.peek_next().type_ == TokenType::Mut) {
+            datatype = Some(self.next());
+        }
+        let mutable: bool = self.expect(&[TokenType::Mut]);
+        let name: Token = self.consume(TokenType::Identifier, "Expect parameter name.");
+
+        Param::new(datatype, mutable, name)
+    }
+
+    fn var_declaration(&mut self, mutable: bool, inferred: bool) -> Var {
+        // the type sits right before the name, or before `mut`
+        let type_: Option<Token> = match inferred {
+            true => None,
+            false => Some(self.tokens.iter().nth(self.current - 1).expect("Expect variable type").clone()),
+        };
         if mutable {
             self.next(); // skip the "mut" keyword
         }
-    
-        let (type_, name) = if inferred {
-            (None, self.consume(TokenType::Identifier, "Expect variable name."))
-        } else {
-            let type_: Option<Token> = Some(self.tokens.iter().nth(self.current - 1).expect("Expect variable type").clone());
-            let name: Token = self.consume(TokenType::Identifier, "Expect variable name.");
-            (type_, name)
-        };
+        let name: Token = self.consume(TokenType::Identifier, "Expect variable name.");
     
         let mut initializer: Option<Box<dyn Expr>> = None;
         if self.expect(&[TokenType::Equal]) {
@@ -127,13 +404,66 @@ impl Parser {
         self.consume(TokenType::Semicolon, "Expect ';' after variable declaration.");
     
         if let Some(type_) = type_ {
-            return Box::new(Var::typed(type_, mutable, name, initializer));
+            return Var::typed(type_, mutable, name, initializer);
         }
     
-        Box::new(Var::inferred(mutable, name, initializer))
+        Var::inferred(mutable, name, initializer)
     }
     
 
+    fn destructure_declaration(&mut self) -> Box<dyn Stmt> {
+        let pattern: VarPattern = self.var_pattern();
+        self.consume(TokenType::Equal, "Expect '=' after destructuring pattern.");
+        let expr: Box<dyn Expr> = self.expression();
+        self.consume(TokenType::Semicolon, "Expect ';' after variable declaration.");
+
+        Box::new(Destructure::new(pattern, expr))
+    }
+
+    fn var_pattern(&mut self) -> VarPattern {
+        // varPattern -> "mut"? IDENTIFIER
+        //             | "(" patternList? ")" | "[" patternList? "]" ;
+        if self.expect(&[TokenType::LParen]) {
+            let paren: Token = self.back();
+            let elements: Vec<VarPattern> = self.var_patterns(TokenType::RParen);
+            self.consume(TokenType::RParen, "Expect ')' after tuple pattern.");
+            return VarPattern::Tuple { paren, elements };
+        }
+        if self.expect(&[TokenType::LSquare]) {
+            let bracket: Token = self.back();
+            let elements: Vec<VarPattern> = self.var_patterns(TokenType::RSquare);
+            self.consume(TokenType::RSquare, "Expect ']' after array pattern.");
+            return VarPattern::Array { bracket, elements };
+        }
+
+        let mutable: bool = self.expect(&[TokenType::Mut]);
+        let name: Token = self.consume(TokenType::Identifier, "Expect variable name in pattern.");
+        VarPattern::Name { name, datatype: None, mutable }
+    }
+
+    fn var_patterns(&mut self, closing: TokenType) -> Vec<VarPattern> {
+        // patternList -> ( ".." )? varPattern ( "," ( ".." )? varPattern )* ;
+        let mut elements: Vec<VarPattern> = Vec::new();
+        let mut has_rest: bool = false;
+
+        while !self.check(closing) && !self.eof() {
+            if self.expect(&[TokenType::DotDot]) {
+                if has_rest {
+                    error(self.back(), "Only one '..' is allowed per pattern.");
+                }
+                has_rest = true;
+                elements.push(VarPattern::Rest(Box::new(self.var_pattern())));
+            } else {
+                elements.push(self.var_pattern());
+            }
+
+            if !self.expect(&[TokenType::Comma]) {
+                break;
+            }
+        }
+        elements
+    }
+
     fn statement(&mut self) -> Box<dyn Stmt> {
         if self.expect(&[TokenType::Echo]) {
             return self.echo_statement();
@@ -147,12 +477,40 @@ impl Parser {
             return self.if_statement();
         }
 
+        if self.check(TokenType::Identifier) && self.peek_next().type_ == TokenType::Colon {
+            return self.labeled_statement();
+        }
+
         if self.expect(&[TokenType::While]) {
-            return self.while_statement();
+            return self.while_statement(None);
         }
 
         if self.expect(&[TokenType::For]) {
-            return self.for_statement();
+            return self.for_statement(None);
+        }
+
+        if self.expect(&[TokenType::Break, TokenType::Continue]) {
+            return self.loop_control_statement();
+        }
+
+        if self.expect(&[TokenType::Return]) {
+            return self.return_statement();
+        }
+
+        if self.expect(&[TokenType::Match]) {
+            return self.match_statement();
+        }
+
+        if self.expect(&[TokenType::Throw]) {
+            return self.throw_statement();
+        }
+
+        if self.expect(&[TokenType::Try]) {
+            return self.try_statement();
+        }
+
+        if self.expect(&[TokenType::Defer]) {
+            return self.defer_statement();
         }
 
         return self.expression_statement();
@@ -177,33 +535,188 @@ impl Parser {
         let then_branch: Box<dyn Stmt> = self.statement();
         let mut else_branch: Option<Box<dyn Stmt>> = None;
 
-        if self.expect(&[TokenType::Else]) {
+        // `elif (...)` is an `else` branch holding the rest of the chain
+        if self.expect(&[TokenType::Elif]) {
+            else_branch = Some(self.if_statement());
+        } else if self.expect(&[TokenType::Else]) {
             else_branch = Some(self.statement());
         }
 
         return Box::new(If::new(condition, then_branch, else_branch));
     }
 
-    fn while_statement(&mut self) -> Box<dyn Stmt> {
+    fn labeled_statement(&mut self) -> Box<dyn Stmt> {
+        // labeledStmt -> IDENTIFIER ":" ( whileStmt | forStmt ) ;
+        let label: Token = self.next();
+        self.next();
+
+        if self.expect(&[TokenType::While]) {
+            return self.while_statement(Some(label));
+        }
+        if self.expect(&[TokenType::For]) {
+            return self.for_statement(Some(label));
+        }
+        error(self.peek(), "Expect loop after label.");
+    }
+
+    fn loop_body(&mut self, label: &Option<Token>) -> Box<dyn Stmt> {
+        self.loops.push(label.as_ref().map(|label| label.lexeme.clone()));
+        let body: Box<dyn Stmt> = self.statement();
+        self.loops.pop();
+        body
+    }
+
+    fn loop_control_statement(&mut self) -> Box<dyn Stmt> {
+        let keyword: Token = self.back();
+
+        let mut label: Option<Token> = None;
+        if self.check(TokenType::Identifier) {
+            label = Some(self.next());
+        }
+        self.consume(TokenType::Semicolon, "Expect ';' after loop control statement.");
+
+        if self.loops.is_empty() {
+            error(keyword, "Cannot use 'break' or 'continue' outside of a loop.");
+        }
+        if let Some(label) = &label {
+            if !self.loops.iter().any(|loop_| loop_.as_ref() == Some(&label.lexeme)) {
+                error(label.clone(), "No enclosing loop has this label.");
+            }
+        }
+
+        match keyword.type_ {
+            TokenType::Break => Box::new(Break::new(keyword, label)),
+            _ => Box::new(Continue::new(keyword, label)),
+        }
+    }
+
+    fn while_statement(&mut self, label: Option<Token>) -> Box<dyn Stmt> {
         self.consume(TokenType::LParen, "Expect '(' after 'while'.");
         let condition: Box<dyn Expr> = self.expression();
         self.consume(TokenType::RParen, "Expect ')' after condition.");
-        let body: Box<dyn Stmt> = self.statement();
+        let body: Box<dyn Stmt> = self.loop_body(&label);
 
-        return Box::new(While::new(condition, body));
+        return Box::new(While::new(label, condition, body));
     }
 
-    fn for_statement(&mut self) -> Box<dyn Stmt> {
-        // forStmt -> "for" "(" ( IDENTIFIER "in" )? (range | IDENTIFIER) ")" statement ;
-        // range   -> NUMBER ".." NUMBER ;
+    fn for_statement(&mut self, label: Option<Token>) -> Box<dyn Stmt> {
+        // forStmt -> "for" "(" ( varPattern "in" )? expression ")" statement ;
+        let keyword: Token = self.back();
         self.consume(TokenType::LParen, "Expect '(' after 'for'.");
 
-        let mut initializer: Option<Box<dyn Stmt>> = None;
-        if self.expect(&[TokenType::Identifier]) {
-            initializer = Some(Box::new(Var::inferred(true, self.peek(), None)));
+        let mut pattern: Option<VarPattern> = None;
+        if self.pattern_before_in() {
+            pattern = Some(self.var_pattern());
+            self.consume(TokenType::In, "Expect 'in' after loop pattern.");
         }
 
-        todo!()
+        let iterable: Box<dyn Expr> = self.expression();
+        self.consume(TokenType::RParen, "Expect ')' after for clauses.");
+
+        let body: Box<dyn Stmt> = self.loop_body(&label);
+        return Box::new(For::new(keyword, label, pattern, iterable, body));
+    }
+
+    /// Whether the tokens ahead are a loop pattern followed by `in` rather than the iterable itself.
+    fn pattern_before_in(&self) -> bool {
+        let mut depth: usize = 0;
+        for token in &self.tokens[self.current..] {
+            match token.type_ {
+                TokenType::In => return depth == 0,
+                TokenType::LParen | TokenType::LSquare => depth += 1,
+                TokenType::RParen | TokenType::RSquare if depth > 0 => depth -= 1,
+                TokenType::Identifier | TokenType::Mut => {},
+                TokenType::Comma | TokenType::DotDot if depth > 0 => {},
+                _ => return false,
+            }
+        }
+        false
+    }
+
+    fn match_statement(&mut self) -> Box<dyn Stmt> {
+        let keyword: Token = self.back();
+        self.consume(TokenType::LParen, "Expect '(' after 'match'.");
+        let subject: Box<dyn Expr> = self.expression();
+        self.consume(TokenType::RParen, "Expect ')' after match subject.");
+        self.consume(TokenType::LBrace, "Expect '{' before match arms.");
+
+        let mut arms: Vec<MatchArm> = Vec::new();
+        while !self.check(TokenType::RBrace) && !self.eof() {
+            let pattern: Pattern = self.pattern();
+            self.consume(TokenType::Arrow, "Expect '->' after pattern.");
+            let body: Box<dyn Stmt> = self.statement();
+            arms.push(MatchArm::new(pattern, body));
+        }
+
+        self.consume(TokenType::RBrace, "Expect '}' after match arms.");
+        return Box::new(Match::new(keyword, subject, arms));
+    }
+
+    fn pattern(&mut self) -> Pattern {
+        // pattern -> "_" | IDENTIFIER "." IDENTIFIER ( "(" IDENTIFIER ( "," IDENTIFIER )* ")" )? ;
+        let name: Token = self.consume(TokenType::Identifier, "Expect pattern.");
+        if name.lexeme == "_" {
+            return Pattern::Wildcard(name);
+        }
+
+        self.consume(TokenType::Dot, "Expect '.' after enum name in pattern.");
+        let variant: Token = self.consume(TokenType::Identifier, "Expect variant name.");
+
+        let mut bindings: Vec<Token> = Vec::new();
+        if self.expect(&[TokenType::LParen]) {
+            loop {
+                bindings.push(self.consume(TokenType::Identifier, "Expect binding name."));
+                if !self.expect(&[TokenType::Comma]) {
+                    break;
+                }
+            }
+            self.consume(TokenType::RParen, "Expect ')' after bindings.");
+        }
+
+        Pattern::Variant { enum_name: Variable::new(name), variant, bindings }
+    }
+
+    fn return_statement(&mut self) -> Box<dyn Stmt> {
+        let keyword: Token = self.back();
+        let mut value: Option<Box<dyn Expr>> = None;
+        if !self.check(TokenType::Semicolon) {
+            value = Some(self.expression());
+        }
+
+        self.consume(TokenType::Semicolon, "Expect ';' after return value.");
+        return Box::new(Return::new(keyword, value));
+    }
+
+    fn throw_statement(&mut self) -> Box<dyn Stmt> {
+        let keyword: Token = self.back();
+        let value: Box<dyn Expr> = self.expression();
+        self.consume(TokenType::Semicolon, "Expect ';' after thrown value.");
+        return Box::new(Throw::new(keyword, value));
+    }
+
+    fn try_statement(&mut self) -> Box<dyn Stmt> {
+        self.consume(TokenType::LBrace, "Expect '{' after 'try'.");
+        let body: Vec<Box<dyn Stmt>> = self.block();
+
+        self.consume(TokenType::Catch, "Expect 'catch' after try block.");
+        self.consume(TokenType::LParen, "Expect '(' after 'catch'.");
+        let name: Token = self.consume(TokenType::Identifier, "Expect error name.");
+        self.consume(TokenType::RParen, "Expect ')' after error name.");
+
+        self.consume(TokenType::LBrace, "Expect '{' before catch block.");
+        let handler: Vec<Box<dyn Stmt>> = self.block();
+        return Box::new(Try::new(body, name, handler));
+    }
+
+    fn defer_statement(&mut self) -> Box<dyn Stmt> {
+        let keyword: Token = self.back();
+
+        // a deferred statement runs while its scope is being left, it cannot steer loops
+        let loops: Vec<Option<String>> = std::mem::take(&mut self.loops);
+        let statement: Box<dyn Stmt> = self.statement();
+        self.loops = loops;
+
+        return Box::new(Defer::new(keyword, statement.into()));
     }
 
     fn echo_statement(&mut self) -> Box<dyn Stmt> {
@@ -223,7 +736,7 @@ impl Parser {
     }
 
     fn assignment(&mut self) -> Box<dyn Expr> {
-        let expr: Box<dyn Expr> = self.or();
+        let expr: Box<dyn Expr> = self.range();
         
         if self.expect(&[TokenType::Equal]) {
             let equals: Token = self.back();
@@ -234,12 +747,41 @@ impl Parser {
                 return Box::new(Assign::new(name, value));
             }
 
+            if expr.as_any().is::<Get>() {
+                let get: Box<Get> = expr.into_any().downcast::<Get>().unwrap();
+                return Box::new(Set::new(get.object, get.name, value));
+            }
+
+            if expr.as_any().is::<Index>() {
+                let index: Box<Index> = expr.into_any().downcast::<Index>().unwrap();
+                return Box::new(SetIndex::new(index.object, index.bracket, index.index, value));
+            }
+
             error(equals, "Invalid assignment target.");
         }
 
         expr
     }
 
+    fn range(&mut self) -> Box<dyn Expr> {
+        let expr: Box<dyn Expr> = self.or();
+
+        if self.expect(&[TokenType::DotDot, TokenType::DotDotEqual]) {
+            let operator: Token = self.back();
+            let end: Box<dyn Expr> = self.or();
+
+            // `step` is only a keyword right after a range
+            let mut step: Option<Box<dyn Expr>> = None;
+            if self.check(TokenType::Identifier) && self.peek().lexeme == "step" {
+                self.next();
+                step = Some(self.or());
+            }
+            return Box::new(Range::new(expr, operator, end, step));
+        }
+
+        expr
+    }
+
     fn or(&mut self) -> Box<dyn Expr> {
         let mut expr: Box<dyn Expr> = self.and();
 
@@ -279,7 +821,7 @@ impl Parser {
     fn comparison(&mut self) -> Box<dyn Expr> {
         let mut expr: Box<dyn Expr> = self.term();
 
-        while self.expect(&[TokenType::Greater, TokenType::GreaterEqual, TokenType::Less, TokenType::LessEqual]) {
+        while self.expect(&[TokenType::Greater, TokenType::GreaterEqual, TokenType::Less, TokenType::LessEqual, TokenType::In]) {
             let operator: Token = self.back();
             let right: Box<dyn Expr> = self.term();
             expr = Box::new(Binary::new(expr, operator, right));
@@ -301,25 +843,104 @@ impl Parser {
     }
 
     fn factor(&mut self) -> Box<dyn Expr> {
-        let mut expr: Box<dyn Expr> = self.unary();
+        let mut expr: Box<dyn Expr> = self.cast();
 
-        while self.expect(&[TokenType::Slash, TokenType::Star]) {
+        while self.expect(&[TokenType::Slash, TokenType::SlashSlash, TokenType::Percent, TokenType::Star]) {
             let operator: Token = self.back();
-            let right: Box<dyn Expr> = self.unary();
+            let right: Box<dyn Expr> = self.cast();
             expr = Box::new(Binary::new(expr, operator, right));
         }
 
         expr
     }
 
+    fn cast(&mut self) -> Box<dyn Expr> {
+        let mut expr: Box<dyn Expr> = self.unary();
+
+        while self.expect(&[TokenType::As]) {
+            let keyword: Token = self.back();
+            let type_: Token = self.consume(TokenType::Identifier, "Expect a type after 'as'.");
+            if NumericType::from_name(&type_.lexeme).is_none() && !["string", "String", "boolean"].contains(&type_.lexeme.as_str()) {
+                error(type_, "Expect a number type, 'string' or 'boolean' after 'as'.");
+            }
+            expr = Box::new(Cast::new(expr, keyword, type_));
+        }
+
+        expr
+    }
+
     fn unary(&mut self) -> Box<dyn Expr> {
-        if self.expect(&[TokenType::Bang, TokenType::Minus]) {
+        if self.expect(&[TokenType::Bang, TokenType::Minus, TokenType::Typeof, TokenType::Sizeof, TokenType::Nameof]) {
             let operator: Token = self.back();
             let right: Box<dyn Expr> = self.unary();
+
+            let named: bool = right.as_any().is::<Variable>() || right.as_any().is::<Get>();
+            if operator.type_ == TokenType::Nameof && !named {
+                error(operator, "Expect a variable or property after 'nameof'.");
+            }
             return Box::new(Unary::new(operator, right));
         }
 
-        return self.primary();
+        return self.call();
+    }
+
+    fn call(&mut self) -> Box<dyn Expr> {
+        let mut expr: Box<dyn Expr> = self.primary();
+
+        loop {
+            if self.expect(&[TokenType::LParen]) {
+                expr = self.finish_call(expr);
+            } else if self.expect(&[TokenType::Dot]) {
+                let name: Token = self.consume(TokenType::Identifier, "Expect property name after '.'.");
+                expr = Box::new(Get::new(expr, name));
+            } else if self.expect(&[TokenType::LSquare]) {
+                let index: Box<dyn Expr> = self.expression();
+                let bracket: Token = self.consume(TokenType::RSquare, "Expect ']' after index.");
+                expr = Box::new(Index::new(expr, bracket, index));
+            } else {
+                break;
+            }
+        }
+
+        expr
+    }
+
+    fn finish_call(&mut self, callee: Box<dyn Expr>) -> Box<dyn Expr> {
+        let arguments: Vec<Box<dyn Expr>> = self.arguments(TokenType::RParen);
+        let paren: Token = self.consume(TokenType::RParen, "Expect ')' after arguments.");
+        Box::new(Call::new(callee, paren, arguments))
+    }
+
+    /// Comma separated expressions up to (not including) `closing`.
+    fn arguments(&mut self, closing: TokenType) -> Vec<Box<dyn Expr>> {
+        let mut arguments: Vec<Box<dyn Expr>> = Vec::new();
+        if !self.check(closing) {
+            loop {
+                arguments.push(self.expression());
+                if !self.expect(&[TokenType::Comma]) {
+                    break;
+                }
+            }
+        }
+        arguments
+    }
+
+    fn interpolation(&mut self) -> Box<dyn Expr> {
+        // the lexer hands us each hole's tokens between a `StringPart` and the text that follows the hole
+        let mut parts: Vec<Box<dyn Expr>> = vec![Box::new(Literal::new(self.back().literal))];
+        loop {
+            parts.push(self.expression());
+
+            if self.expect(&[TokenType::StringPart]) {
+                parts.push(Box::new(Literal::new(self.back().literal)));
+                continue;
+            }
+            let end: Token = self.consume(TokenType::String, "Expect '}' after interpolated expression.");
+            parts.push(Box::new(Literal::new(end.literal)));
+            break;
+        }
+
+        Box::new(Interpolation::new(parts))
     }
 
     fn primary(&mut self) -> Box<dyn Expr> {
@@ -332,27 +953,102 @@ impl Parser {
             return Box::new(Literal::new(Some(Value::Boolean(true))));
         }
 
-        if self.expect(&[TokenType::Number, TokenType::String]) {
+        if self.expect(&[TokenType::Number]) {
+            let number: Token = self.back();
+            return Box::new(Literal::number(number.literal, !lexer::has_suffix(&number.lexeme)));
+        }
+
+        if self.expect(&[TokenType::String]) {
             return Box::new(Literal::new(self.back().literal));
         }
 
+        if self.expect(&[TokenType::StringPart]) {
+            return self.interpolation();
+        }
+
         if self.expect(&[TokenType::Identifier]) {
             return Box::new(Variable::new(self.back()));
         }
 
-        if self.tokens.iter().nth(self.current - 1).unwrap().clone().type_ == TokenType::Identifier {
-            return Box::new(Variable::new(self.back()));
+        if self.expect(&[TokenType::This]) {
+            return Box::new(This::new(self.back()));
+        }
+
+        if self.expect(&[TokenType::Parent]) {
+            let keyword: Token = self.back();
+            self.consume(TokenType::Dot, "Expect '.' after 'parent'.");
+            let method: Token = self.consume(TokenType::Identifier, "Expect parent method name.");
+            return Box::new(Parent::new(keyword, method));
+        }
+
+        if self.expect(&[TokenType::New]) {
+            let keyword: Token = self.back();
+            let entity: Token = self.consume(TokenType::Identifier, "Expect entity name after 'new'.");
+            self.consume(TokenType::LParen, "Expect '(' after entity name.");
+            let arguments: Vec<Box<dyn Expr>> = self.arguments(TokenType::RParen);
+            self.consume(TokenType::RParen, "Expect ')' after arguments.");
+            return Box::new(New { keyword, entity: Box::new(Variable::new(entity)), arguments });
         }
 
         if self.expect(&[TokenType::LParen]) {
+            let paren: Token = self.back();
+            if self.expect(&[TokenType::RParen]) {
+                return Box::new(Tuple::new(paren, Vec::new()));
+            }
+
             let expr: Box<dyn Expr> = self.expression();
-            self.consume(TokenType::RParen, "Expect ')' after expression");
-            return Box::new(Grouping::new(expr));
+            if !self.expect(&[TokenType::Comma]) {
+                self.consume(TokenType::RParen, "Expect ')' after expression");
+                return Box::new(Grouping::new(expr));
+            }
+
+            // a comma makes it a tuple, `(a,)` has a single element
+            let mut elements: Vec<Box<dyn Expr>> = vec![expr];
+            while !self.check(TokenType::RParen) && !self.eof() {
+                elements.push(self.expression());
+                if !self.expect(&[TokenType::Comma]) {
+                    break;
+                }
+            }
+            self.consume(TokenType::RParen, "Expect ')' after tuple elements.");
+            return Box::new(Tuple::new(paren, elements));
+        }
+
+        if self.expect(&[TokenType::LSquare]) {
+            let bracket: Token = self.back();
+            let elements: Vec<Box<dyn Expr>> = self.arguments(TokenType::RSquare);
+            self.consume(TokenType::RSquare, "Expect ']' after array elements.");
+            return Box::new(Array::new(bracket, elements));
+        }
+
+        // statements starting with '{' are blocks, here it can only be a map
+        if self.expect(&[TokenType::LBrace]) {
+            return self.map();
         }
 
         error(self.peek(), "Expect Expression");
     }
 
+    fn map(&mut self) -> Box<dyn Expr> {
+        // map -> "{" ( expression ":" expression ( "," expression ":" expression )* ","? )? "}" ;
+        let brace: Token = self.back();
+
+        let mut entries: Vec<(Box<dyn Expr>, Box<dyn Expr>)> = Vec::new();
+        while !self.check(TokenType::RBrace) && !self.eof() {
+            let key: Box<dyn Expr> = self.expression();
+            self.consume(TokenType::Colon, "Expect ':' after map key.");
+            let value: Box<dyn Expr> = self.expression();
+            entries.push((key, value));
+
+            if !self.expect(&[TokenType::Comma]) {
+                break;
+            }
+        }
+
+        self.consume(TokenType::RBrace, "Expect '}' after map entries.");
+        Box::new(Map::new(brace, entries))
+    }
+
     fn synchronize(&mut self) {
         self.next();
 
@@ -366,7 +1062,7 @@ impl Parser {
             TokenType::Entity | TokenType::Trait | TokenType::Set | TokenType::Catch | TokenType::If |
             TokenType::Else | TokenType::Elif | TokenType::While | TokenType::Unreachable | TokenType::Void |
             TokenType::Typeof | TokenType::Nameof | TokenType::Sizeof | TokenType::Echo |
-            TokenType::Todo | TokenType::Test | TokenType::Override | TokenType::Open | TokenType::Scene => {
+            TokenType::Todo | TokenType::Test | TokenType::Override | TokenType::Open | TokenType::Scene | TokenType::Return | TokenType::Match | TokenType::Enum | TokenType::Throw | TokenType::Try | TokenType::Defer | TokenType::Break | TokenType::Continue => {
                 return;
             },
             _ => {}
@@ -374,4 +1070,18 @@ impl Parser {
 
         self.next();
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+fn parse_source(source: &str) -> Vec<Box<dyn Stmt>> {
+    use crate::lexer::Lexer;
+
+    let tokens: Vec<Token> = Lexer::new(source.to_string()).tokenize();
+    Parser::new(tokens).parse()
+}
+
+#[test]
+#[should_panic(expected = "outside of a loop")]
+fn test_break_outside_loop() {
+    parse_source("while (true) { set f() { break; } }");
+}
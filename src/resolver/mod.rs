@@ -0,0 +1,583 @@
+// static pass between the parser and the interpreter: binds every local
+// variable to the scope depth and slot it lives in.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::lexer::tokens::{Token, TokenType};
+use crate::parser::ast::{Binding, Expr, Function, Pattern, Stmt, VarPattern, Visitor};
+use crate::util::error_formatter::{ErrorHandler, ErrorKind};
+use crate::util::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FunctionType {
+    None,
+    Function,
+    Method,
+    Initializer,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EntityType {
+    None,
+    Entity,
+    Subentity,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Local {
+    slot: usize,
+    defined: bool,
+}
+
+pub struct Resolver {
+    scopes: Vec<HashMap<String, Local>>,
+    globals: HashSet<String>,
+    // global names read inside functions, checked once the whole program is known
+    unresolved: Vec<Token>,
+    current_function: FunctionType,
+    // whether the statement being resolved runs deferred, leaving it with `return` is not allowed
+    in_defer: bool,
+    current_entity: EntityType,
+    // innermost entity whose body is being resolved, used for visibility checks
+    current_entity_name: Option<String>,
+    // variant names and payload sizes of every enum declared so far, for match exhaustiveness
+    enums: HashMap<String, Vec<(String, usize)>>,
+    error_handler: ErrorHandler,
+}
+
+impl Default for Resolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Resolver {
+    pub fn new() -> Self {
+        Self {
+            scopes: Vec::new(),
+            globals: HashSet::new(),
+            unresolved: Vec::new(),
+            current_function: FunctionType::None,
+            in_defer: false,
+            current_entity: EntityType::None,
+            current_entity_name: None,
+            enums: HashMap::new(),
+            error_handler: ErrorHandler,
+        }
+    }
+
+    pub fn resolve(&mut self, stmts: &Vec<Box<dyn Stmt>>) {
+        self.resolve_stmts(stmts);
+
+        for name in &self.unresolved {
+            if !self.globals.contains(&name.lexeme) {
+                self.error_handler.throw(ErrorKind::UndefinedVar(name.clone()));
+            }
+        }
+    }
+
+    fn resolve_stmts(&mut self, stmts: &Vec<Box<dyn Stmt>>) {
+        for stmt in stmts {
+            stmt.accept(self);
+        }
+    }
+
+    fn resolve_expr(&mut self, expr: &dyn Expr) {
+        expr.accept(self);
+    }
+
+    fn begin_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    fn end_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn declare(&mut self, name: &Token) {
+        let Some(scope) = self.scopes.last_mut() else {
+            self.globals.insert(name.lexeme.clone());
+            return;
+        };
+
+        if scope.contains_key(&name.lexeme) {
+            self.error_handler.throw(ErrorKind::AlreadyDeclared(name.clone()));
+        }
+
+        // slots are handed out in declaration order, the same order the
+        // interpreter defines them in at runtime
+        let slot: usize = scope.len();
+        scope.insert(name.lexeme.clone(), Local { slot, defined: false });
+    }
+
+    fn define(&mut self, name: &Token) {
+        if let Some(local) = self.scopes.last_mut().and_then(|scope| scope.get_mut(&name.lexeme)) {
+            local.defined = true;
+        }
+    }
+
+    fn resolve_local(&mut self, name: &Token) -> Option<Binding> {
+        for (depth, scope) in self.scopes.iter().rev().enumerate() {
+            if let Some(local) = scope.get(&name.lexeme) {
+                return Some(Binding::new(depth, local.slot));
+            }
+        }
+
+        // top-level code runs in order, so the global has to exist already;
+        // function bodies may refer to globals declared after them
+        if self.current_function == FunctionType::None {
+            if !self.globals.contains(&name.lexeme) {
+                self.error_handler.throw(ErrorKind::UndefinedVar(name.clone()));
+            }
+        } else {
+            self.unresolved.push(name.clone());
+        }
+
+        None
+    }
+
+    /// Declares and defines every name `pattern` binds, the same way the interpreter binds them.
+    fn bind_pattern(&mut self, pattern: &VarPattern) {
+        for name in pattern.names() {
+            self.declare(name);
+            self.define(name);
+        }
+    }
+
+    fn resolve_function(&mut self, function: &Function, type_: FunctionType) {
+        let enclosing: FunctionType = self.current_function;
+        self.current_function = type_;
+        let in_defer: bool = std::mem::replace(&mut self.in_defer, false);
+
+        self.begin_scope();
+        for param in &function.params {
+            self.bind_pattern(&param.pattern());
+        }
+        self.resolve_stmts(&function.body);
+        self.end_scope();
+
+        self.current_function = enclosing;
+        self.in_defer = in_defer;
+    }
+}
+
+impl Visitor for Resolver {
+    fn visit_binary(&mut self, binary: &crate::parser::ast::Binary) -> Value {
+        self.resolve_expr(&*binary.left);
+        self.resolve_expr(&*binary.right);
+        Value::Null
+    }
+
+    fn visit_grouping(&mut self, grouping: &crate::parser::ast::Grouping) -> Value {
+        self.resolve_expr(&*grouping.expression);
+        Value::Null
+    }
+
+    fn visit_literal(&self, _literal: &crate::parser::ast::Literal) -> Value {
+        Value::Null
+    }
+
+    fn visit_interpolation(&mut self, interpolation: &crate::parser::ast::Interpolation) -> Value {
+        for part in &interpolation.parts {
+            self.resolve_expr(&**part);
+        }
+        Value::Null
+    }
+
+    fn visit_cast(&mut self, cast: &crate::parser::ast::Cast) -> Value {
+        self.resolve_expr(&*cast.value);
+        Value::Null
+    }
+
+    fn visit_unary(&mut self, unary: &crate::parser::ast::Unary) -> Value {
+        self.resolve_expr(&*unary.right);
+        Value::Null
+    }
+
+    fn visit_logical(&mut self, logical: &crate::parser::ast::Logical) -> Value {
+        self.resolve_expr(&*logical.lhs);
+        self.resolve_expr(&*logical.rhs);
+        Value::Null
+    }
+
+    fn visit_variable(&mut self, variable: &crate::parser::ast::Variable) -> Value {
+        if let Some(local) = self.scopes.last().and_then(|scope| scope.get(&variable.name.lexeme)) {
+            if !local.defined {
+                self.error_handler.throw(ErrorKind::OwnInitializer(variable.name.clone()));
+            }
+        }
+
+        variable.binding.set(self.resolve_local(&variable.name));
+        Value::Null
+    }
+
+    fn visit_assign(&mut self, assign: &crate::parser::ast::Assign) -> Value {
+        self.resolve_expr(&*assign.value);
+        assign.binding.set(self.resolve_local(&assign.name));
+        Value::Null
+    }
+
+    fn visit_call(&mut self, call: &crate::parser::ast::Call) -> Value {
+        self.resolve_expr(&*call.callee);
+        for argument in &call.arguments {
+            self.resolve_expr(&**argument);
+        }
+        Value::Null
+    }
+
+    fn visit_array(&mut self, array: &crate::parser::ast::Array) -> Value {
+        for element in &array.elements {
+            self.resolve_expr(&**element);
+        }
+        Value::Null
+    }
+
+    fn visit_tuple(&mut self, tuple: &crate::parser::ast::Tuple) -> Value {
+        for element in &tuple.elements {
+            self.resolve_expr(&**element);
+        }
+        Value::Null
+    }
+
+    fn visit_map(&mut self, map: &crate::parser::ast::Map) -> Value {
+        for (key, value) in &map.entries {
+            self.resolve_expr(&**key);
+            self.resolve_expr(&**value);
+        }
+        Value::Null
+    }
+
+    fn visit_index(&mut self, index: &crate::parser::ast::Index) -> Value {
+        self.resolve_expr(&*index.object);
+        self.resolve_expr(&*index.index);
+        Value::Null
+    }
+
+    fn visit_set_index(&mut self, set_index: &crate::parser::ast::SetIndex) -> Value {
+        self.resolve_expr(&*set_index.value);
+        self.resolve_expr(&*set_index.object);
+        self.resolve_expr(&*set_index.index);
+        Value::Null
+    }
+
+    fn visit_get(&mut self, get: &crate::parser::ast::Get) -> Value {
+        get.accessor.replace(self.current_entity_name.clone());
+        self.resolve_expr(&*get.object);
+        Value::Null
+    }
+
+    fn visit_set(&mut self, set: &crate::parser::ast::Set) -> Value {
+        set.accessor.replace(self.current_entity_name.clone());
+        self.resolve_expr(&*set.value);
+        self.resolve_expr(&*set.object);
+        Value::Null
+    }
+
+    fn visit_this(&mut self, this: &crate::parser::ast::This) -> Value {
+        if self.current_entity == EntityType::None {
+            self.error_handler.throw(ErrorKind::ThisOutsideEntity(this.keyword.clone()));
+        }
+
+        this.binding.set(self.resolve_local(&this.keyword));
+        Value::Null
+    }
+
+    fn visit_new(&mut self, new: &crate::parser::ast::New) -> Value {
+        self.resolve_expr(&*new.entity);
+        for argument in &new.arguments {
+            self.resolve_expr(&**argument);
+        }
+        Value::Null
+    }
+
+    fn visit_parent(&mut self, parent: &crate::parser::ast::Parent) -> Value {
+        if self.current_entity != EntityType::Subentity {
+            self.error_handler.throw(ErrorKind::ParentOutsideSubentity(parent.keyword.clone()));
+        }
+
+        parent.binding.set(self.resolve_local(&parent.keyword));
+        Value::Null
+    }
+
+    fn visit_range(&mut self, range: &crate::parser::ast::Range) -> Value {
+        self.resolve_expr(&*range.start);
+        self.resolve_expr(&*range.end);
+        if let Some(step) = &range.step {
+            self.resolve_expr(&**step);
+        }
+        Value::Null
+    }
+
+    fn visit_expr_stmt(&mut self, expr: &crate::parser::ast::Expression) {
+        self.resolve_expr(&*expr.expr);
+    }
+
+    fn visit_echo_stmt(&mut self, echo: &crate::parser::ast::Echo) {
+        self.resolve_expr(&*echo.expr);
+    }
+
+    fn visit_block_stmt(&mut self, block: &crate::parser::ast::Block) {
+        self.begin_scope();
+        self.resolve_stmts(&block.statements);
+        self.end_scope();
+    }
+
+    fn visit_if_stmt(&mut self, if_: &crate::parser::ast::If) {
+        self.resolve_expr(&*if_.condition);
+        if_.then_branch.accept(self);
+        if let Some(else_branch) = &if_.else_branch {
+            else_branch.accept(self);
+        }
+    }
+
+    fn visit_while_stmt(&mut self, while_: &crate::parser::ast::While) {
+        self.resolve_expr(&*while_.condition);
+        while_.body.accept(self);
+    }
+
+    fn visit_break_stmt(&mut self, _break: &crate::parser::ast::Break) {}
+
+    fn visit_continue_stmt(&mut self, _continue: &crate::parser::ast::Continue) {}
+
+    fn visit_for_stmt(&mut self, for_: &crate::parser::ast::For) {
+        self.resolve_expr(&*for_.iterable);
+
+        // every iteration gets a scope of its own holding the loop variable
+        self.begin_scope();
+        if let Some(pattern) = &for_.pattern {
+            self.bind_pattern(pattern);
+        }
+        for_.body.accept(self);
+        self.end_scope();
+    }
+
+    fn visit_match_stmt(&mut self, match_: &crate::parser::ast::Match) {
+        self.resolve_expr(&*match_.subject);
+
+        let mut matched_enum: Option<String> = None;
+        let mut covered: HashSet<String> = HashSet::new();
+        let mut wildcard: bool = false;
+
+        for arm in &match_.arms {
+            let mut bindings: &[Token] = &[];
+
+            match &arm.pattern {
+                Pattern::Wildcard(_) => wildcard = true,
+                Pattern::Variant { enum_name, variant, bindings: names } => {
+                    enum_name.accept(self);
+
+                    let Some(variants) = self.enums.get(&enum_name.name.lexeme) else {
+                        self.error_handler.throw(ErrorKind::NotAnEnum(enum_name.name.clone()));
+                    };
+                    let Some((_, arity)) = variants.iter().find(|(name, _)| *name == variant.lexeme) else {
+                        self.error_handler.throw(ErrorKind::UnknownVariant(variant.clone(), enum_name.name.lexeme.clone()));
+                    };
+                    if *arity != names.len() {
+                        self.error_handler.throw(ErrorKind::ArityMismatch(variant.clone(), *arity, names.len()));
+                    }
+
+                    covered.insert(variant.lexeme.clone());
+                    matched_enum = Some(enum_name.name.lexeme.clone());
+                    bindings = names;
+                },
+            }
+
+            self.begin_scope();
+            for binding in bindings {
+                if binding.lexeme != "_" {
+                    self.declare(binding);
+                    self.define(binding);
+                }
+            }
+            arm.body.accept(self);
+            self.end_scope();
+        }
+
+        if let (Some(enum_name), false) = (matched_enum, wildcard) {
+            let missing: Vec<String> = self.enums[&enum_name].iter()
+                .filter(|(name, _)| !covered.contains(name))
+                .map(|(name, _)| format!("{enum_name}.{name}"))
+                .collect();
+
+            if !missing.is_empty() {
+                self.error_handler.throw(ErrorKind::NonExhaustiveMatch(match_.keyword.clone(), missing));
+            }
+        }
+    }
+
+    fn visit_return_stmt(&mut self, return_: &crate::parser::ast::Return) {
+        if self.current_function == FunctionType::None {
+            self.error_handler.throw(ErrorKind::TopLevelReturn(return_.keyword.clone()));
+        }
+        if self.in_defer {
+            self.error_handler.throw(ErrorKind::ReturnInDefer(return_.keyword.clone()));
+        }
+
+        if let Some(value) = &return_.value {
+            if self.current_function == FunctionType::Initializer {
+                self.error_handler.throw(ErrorKind::InitializerReturn(return_.keyword.clone()));
+            }
+            self.resolve_expr(&**value);
+        }
+    }
+
+    fn visit_throw_stmt(&mut self, throw: &crate::parser::ast::Throw) {
+        self.resolve_expr(&*throw.value);
+    }
+
+    fn visit_try_stmt(&mut self, try_: &crate::parser::ast::Try) {
+        self.begin_scope();
+        self.resolve_stmts(&try_.body);
+        self.end_scope();
+
+        // the caught error lives in the same scope as the handler's statements
+        self.begin_scope();
+        self.declare(&try_.name);
+        self.define(&try_.name);
+        self.resolve_stmts(&try_.handler);
+        self.end_scope();
+    }
+
+    fn visit_defer_stmt(&mut self, defer: &crate::parser::ast::Defer) {
+        let enclosing: bool = std::mem::replace(&mut self.in_defer, true);
+        defer.statement.accept(self);
+        self.in_defer = enclosing;
+    }
+
+    fn visit_destructure_decl(&mut self, destructure: &crate::parser::ast::Destructure) {
+        let names: Vec<&Token> = destructure.pattern.names();
+        for name in &names {
+            self.declare(name);
+        }
+        self.resolve_expr(&*destructure.expr);
+        for name in &names {
+            self.define(name);
+        }
+    }
+
+    fn visit_var_decl(&mut self, var: &crate::parser::ast::Var) {
+        self.declare(&var.name);
+        if let Some(expr) = &var.expr {
+            self.resolve_expr(&**expr);
+        }
+        self.define(&var.name);
+    }
+
+    fn visit_function_decl(&mut self, function: &crate::parser::ast::Function) {
+        // defined before the body is resolved so the function can recurse
+        self.declare(&function.name);
+        self.define(&function.name);
+
+        self.resolve_function(function, FunctionType::Function);
+    }
+
+    fn visit_entity_decl(&mut self, entity: &crate::parser::ast::Entity) {
+        let enclosing: EntityType = self.current_entity;
+        self.current_entity = EntityType::Entity;
+        let enclosing_name: Option<String> = self.current_entity_name.replace(entity.name.lexeme.clone());
+
+        self.declare(&entity.name);
+        self.define(&entity.name);
+
+        if let Some(parent) = &entity.parent {
+            if parent.name.lexeme == entity.name.lexeme {
+                self.error_handler.throw(ErrorKind::InheritFromSelf(parent.name.clone()));
+            }
+            self.current_entity = EntityType::Subentity;
+            parent.accept(self);
+
+            // `parent` lives one scope above `this`
+            self.begin_scope();
+            self.scopes.last_mut().unwrap().insert("parent".to_string(), Local { slot: 0, defined: true });
+        }
+
+        for trait_ in &entity.traits {
+            trait_.accept(self);
+        }
+
+        // methods and field initializers share a scope holding `this` in slot 0
+        self.begin_scope();
+        self.scopes.last_mut().unwrap().insert("this".to_string(), Local { slot: 0, defined: true });
+
+        for field in entity.fields.iter() {
+            if let Some(expr) = &field.expr {
+                self.resolve_expr(&**expr);
+            }
+        }
+
+        for method in &entity.methods {
+            let type_: FunctionType = match method.name.type_ {
+                TokenType::Init => FunctionType::Initializer,
+                _ => FunctionType::Method,
+            };
+            self.resolve_function(method, type_);
+        }
+
+        self.end_scope();
+        if entity.parent.is_some() {
+            self.end_scope();
+        }
+        self.current_entity = enclosing;
+        self.current_entity_name = enclosing_name;
+    }
+
+    fn visit_trait_decl(&mut self, trait_: &crate::parser::ast::Trait) {
+        let enclosing: EntityType = self.current_entity;
+        self.current_entity = EntityType::Entity;
+        // default methods only see the public members of the implementing entity
+        let enclosing_name: Option<String> = self.current_entity_name.take();
+
+        self.declare(&trait_.name);
+        self.define(&trait_.name);
+
+        // default methods are bound like entity methods, with `this` in slot 0
+        self.begin_scope();
+        self.scopes.last_mut().unwrap().insert("this".to_string(), Local { slot: 0, defined: true });
+        for method in &trait_.defaults {
+            self.resolve_function(method, FunctionType::Method);
+        }
+        self.end_scope();
+
+        self.current_entity = enclosing;
+        self.current_entity_name = enclosing_name;
+    }
+
+    fn visit_enum_decl(&mut self, enum_: &crate::parser::ast::Enum) {
+        self.declare(&enum_.name);
+        self.define(&enum_.name);
+
+        let variants: Vec<(String, usize)> = enum_.variants.iter().map(|variant| (variant.name.lexeme.clone(), variant.fields.len())).collect();
+        self.enums.insert(enum_.name.lexeme.clone(), variants);
+    }
+
+    fn visit_export_stmt(&mut self, export: &crate::parser::ast::Export) {
+        export.declaration.accept(self);
+    }
+}
+
+#[cfg(test)]
+fn resolve_source(source: &str) {
+    use crate::{lexer::Lexer, parser::Parser};
+
+    let tokens: Vec<Token> = Lexer::new(source.to_string()).tokenize();
+    let stmts: Vec<Box<dyn Stmt>> = Parser::new(tokens).parse();
+    Resolver::new().resolve(&stmts);
+}
+
+#[test]
+#[should_panic(expected = "Variable already declared")]
+fn test_redeclared_in_same_scope() {
+    resolve_source("{ set a = 1; set a = 2; }");
+}
+
+#[test]
+#[should_panic(expected = "Variable used in its own initializer")]
+fn test_own_initializer() {
+    resolve_source("{ set a = a; }");
+}
+
+#[test]
+#[should_panic(expected = "Undefined Variable")]
+fn test_undefined_before_running() {
+    resolve_source("set f() { return missing; }");
+}
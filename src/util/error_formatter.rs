@@ -6,7 +6,9 @@ use std::fs::{self, File};
 use std::io::{self, BufRead};
 use std::path::Path;
 
+use crate::interpreter::numeric::NumericType;
 use crate::lexer::tokens::{Token, TokenType};
+use crate::parser::ast::Visibility;
 use crate::util::print_formatter::StringFormat;
 
 use super::Value;
@@ -20,23 +22,145 @@ pub enum ErrorKind {
     TypeMismatch(Token, String, Vec<String>),
     ImmutableVar(Token, String),
     UndefinedVar(Token),
+    UninitializedVar(Token),
+    NotCallable(Token),
+    ArityMismatch(Token, usize, usize),
+    AlreadyDeclared(Token),
+    OwnInitializer(Token),
+    TopLevelReturn(Token),
+    UndefinedProperty(Token),
+    NotAnInstance(Token),
+    NotAnEntity(Token),
+    ThisOutsideEntity(Token),
+    InitializerReturn(Token),
+    InheritFromSelf(Token),
+    EntityNotOpen(Token, String),
+    MissingOverride(Token),
+    NothingToOverride(Token),
+    ParentOutsideSubentity(Token),
+    NotATrait(Token),
+    MissingTraitMethod(Token, String, String),
+    TraitSignatureMismatch(Token, String, usize, usize),
+    InaccessibleMember(Token, String, Visibility),
+    NotAnEnum(Token),
+    UnknownVariant(Token, String),
+    NonExhaustiveMatch(Token, Vec<String>),
+    NoMatchingArm(Token, Value),
+    Uncaught(Token, Value),
+    ReturnInDefer(Token),
+    RangeBound(Token, Value),
+    ZeroStep(Token),
+    NotIterable(Token, Value),
+    NotIndexable(Token, Value),
+    InvalidIndex(Token, Value),
+    IndexOutOfBounds(Token, i128, usize),
+    InvalidMapKey(Token, Value),
+    MissingKey(Token, Value),
+    NotAContainer(Token, Value),
+    DestructureMismatch(Token, String, Value),
+    InvalidEscape(Token, &'static str),
+    InvalidOperands(Token, Value, Value),
+    IntegerOverflow(Token, String, &'static str),
+    OutOfRange(Token, String, &'static str),
+    MalformedNumber(Token, String),
+    InvalidCast(Token, Value, String),
+    NoSize(Token, Value),
+    DivisionByZero(Token),
+    NegativeRepeat(Token, i128),
     Fatal,
 }
 
 #[derive(Clone, Debug)]
 pub struct ErrorHandler;
 
+/// Everything `default_error_design` needs to report an error.
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    pub title: String,
+    pub line: usize,
+    pub row: usize,
+    pub code: String,
+    pub description: String,
+    pub help: Option<String>,
+    pub note: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn new(title: &str, line: usize, row: usize, code: &str, description: &str, help: Option<&str>, note: Option<&str>) -> Self {
+        Self {
+            title: title.to_string(),
+            line,
+            row,
+            code: code.to_string(),
+            description: description.to_string(),
+            help: help.map(str::to_string),
+            note: note.map(str::to_string),
+        }
+    }
+}
+
 impl ErrorHandler {
     pub fn throw(&self, error: ErrorKind) -> ! {
+        let diagnostic: Diagnostic = self.diagnose(error);
+        self.default_error_design(&diagnostic.title, diagnostic.line, diagnostic.row, &diagnostic.code, &diagnostic.description, diagnostic.help.as_deref(), diagnostic.note.as_deref());
+    }
+
+    /// Builds the message for `error` without reporting it.
+    pub fn diagnose(&self, error: ErrorKind) -> Diagnostic {
         match error {
-            ErrorKind::NumberOperand(token) => self.throw_number_operand_error(token),
-            ErrorKind::UnexpectedToken(token, msg) => self.throw_unexpected_token_error(token, msg),
-            ErrorKind::UnkownToken(token) => self.throw_unkown_token_error(token),
-            ErrorKind::UnexpectedType(token, value) => self.throw_unexpected_type_error(value, token),
-            ErrorKind::TypeMismatch(token, input, expected) => self.throw_type_mismatch_error(token, input, expected),
-            ErrorKind::ImmutableVar(token, name) => self.throw_immutable_var_error(token, name),
-            ErrorKind::UndefinedVar(token) => self.throw_undefined_var_error(token),
-            ErrorKind::Fatal => self.throw_fatal_error()
+            ErrorKind::NumberOperand(token) => self.number_operand_error(token),
+            ErrorKind::UnexpectedToken(token, msg) => self.unexpected_token_error(token, msg),
+            ErrorKind::UnkownToken(token) => self.unkown_token_error(token),
+            ErrorKind::UnexpectedType(token, value) => self.unexpected_type_error(value, token),
+            ErrorKind::TypeMismatch(token, input, expected) => self.type_mismatch_error(token, input, expected),
+            ErrorKind::ImmutableVar(token, name) => self.immutable_var_error(token, name),
+            ErrorKind::UndefinedVar(token) => self.undefined_var_error(token),
+            ErrorKind::UninitializedVar(token) => self.uninitialized_var_error(token),
+            ErrorKind::NotCallable(token) => self.not_callable_error(token),
+            ErrorKind::ArityMismatch(token, expected, got) => self.arity_mismatch_error(token, expected, got),
+            ErrorKind::AlreadyDeclared(token) => self.already_declared_error(token),
+            ErrorKind::OwnInitializer(token) => self.own_initializer_error(token),
+            ErrorKind::TopLevelReturn(token) => self.top_level_return_error(token),
+            ErrorKind::UndefinedProperty(token) => self.undefined_property_error(token),
+            ErrorKind::NotAnInstance(token) => self.not_an_instance_error(token),
+            ErrorKind::NotAnEntity(token) => self.not_an_entity_error(token),
+            ErrorKind::ThisOutsideEntity(token) => self.this_outside_entity_error(token),
+            ErrorKind::InitializerReturn(token) => self.initializer_return_error(token),
+            ErrorKind::InheritFromSelf(token) => self.inherit_from_self_error(token),
+            ErrorKind::EntityNotOpen(token, name) => self.entity_not_open_error(token, name),
+            ErrorKind::MissingOverride(token) => self.missing_override_error(token),
+            ErrorKind::NothingToOverride(token) => self.nothing_to_override_error(token),
+            ErrorKind::ParentOutsideSubentity(token) => self.parent_outside_subentity_error(token),
+            ErrorKind::NotATrait(token) => self.not_a_trait_error(token),
+            ErrorKind::MissingTraitMethod(token, entity, trait_) => self.missing_trait_method_error(token, entity, trait_),
+            ErrorKind::TraitSignatureMismatch(token, method, expected, got) => self.trait_signature_mismatch_error(token, method, expected, got),
+            ErrorKind::InaccessibleMember(token, owner, visibility) => self.inaccessible_member_error(token, owner, visibility),
+            ErrorKind::NotAnEnum(token) => self.not_an_enum_error(token),
+            ErrorKind::UnknownVariant(token, enum_) => self.unknown_variant_error(token, enum_),
+            ErrorKind::NonExhaustiveMatch(token, missing) => self.non_exhaustive_match_error(token, missing),
+            ErrorKind::NoMatchingArm(token, value) => self.no_matching_arm_error(token, value),
+            ErrorKind::Uncaught(token, value) => self.uncaught_error(token, value),
+            ErrorKind::ReturnInDefer(token) => self.return_in_defer_error(token),
+            ErrorKind::RangeBound(token, value) => self.range_bound_error(token, value),
+            ErrorKind::ZeroStep(token) => self.zero_step_error(token),
+            ErrorKind::NotIterable(token, value) => self.not_iterable_error(token, value),
+            ErrorKind::NotIndexable(token, value) => self.not_indexable_error(token, value),
+            ErrorKind::InvalidIndex(token, value) => self.invalid_index_error(token, value),
+            ErrorKind::IndexOutOfBounds(token, index, len) => self.index_out_of_bounds_error(token, index, len),
+            ErrorKind::InvalidMapKey(token, value) => self.invalid_map_key_error(token, value),
+            ErrorKind::MissingKey(token, value) => self.missing_key_error(token, value),
+            ErrorKind::NotAContainer(token, value) => self.not_a_container_error(token, value),
+            ErrorKind::DestructureMismatch(token, expected, value) => self.destructure_mismatch_error(token, expected, value),
+            ErrorKind::InvalidEscape(token, msg) => self.invalid_escape_error(token, msg),
+            ErrorKind::InvalidOperands(token, lhs, rhs) => self.invalid_operands_error(token, lhs, rhs),
+            ErrorKind::IntegerOverflow(token, expression, type_) => self.integer_overflow_error(token, expression, type_),
+            ErrorKind::OutOfRange(token, value, type_) => self.out_of_range_error(token, value, type_),
+            ErrorKind::MalformedNumber(token, msg) => self.malformed_number_error(token, msg),
+            ErrorKind::InvalidCast(token, value, type_) => self.invalid_cast_error(token, value, type_),
+            ErrorKind::NoSize(token, value) => self.no_size_error(token, value),
+            ErrorKind::DivisionByZero(token) => self.division_by_zero_error(token),
+            ErrorKind::NegativeRepeat(token, count) => self.negative_repeat_error(token, count),
+            ErrorKind::Fatal => self.fatal_error()
         }
     }  
 
@@ -107,23 +231,23 @@ impl ErrorHandler {
         panic!("{}", error);
     }
 
-    fn throw_number_operand_error(&self, token: Token) -> ! {
-        self.default_error_design("Wrong number-operand order", token.line, token.row, token.lexeme.as_str(), "Expected number after Operand in Expression", None, None);
+    fn number_operand_error(&self, token: Token) -> Diagnostic {
+        Diagnostic::new("Wrong number-operand order", token.line, token.row, token.lexeme.as_str(), "Expected number after Operand in Expression", None, None)
     }
 
-    fn throw_unkown_token_error(&self, token: Token) -> ! {
-        self.default_error_design("Unexpected Token", token.line, token.row, &token.lexeme, "Unexpected token found here", Some("Remove this token."), None);
+    fn unkown_token_error(&self, token: Token) -> Diagnostic {
+        Diagnostic::new("Unexpected Token", token.line, token.row, &token.lexeme, "Unexpected token found here", Some("Remove this token."), None)
     }
 
-    fn throw_unexpected_token_error(&self, token: Token, msg: &str) -> ! {
-        self.default_error_design("Unexpected Token", token.line, token.row, &token.lexeme, msg, None, None);
+    fn unexpected_token_error(&self, token: Token, msg: &str) -> Diagnostic {
+        Diagnostic::new("Unexpected Token", token.line, token.row, &token.lexeme, msg, None, None)
     }
 
-    fn throw_unexpected_type_error(&self, value: Value, token: Token) -> ! {
-        self.default_error_design("Unexpected Type", token.line, token.row, &token.lexeme, format!("Expected type `f64`, got value of `{}`", value).as_str(), Some("Change to type `f64`."), None);
+    fn unexpected_type_error(&self, value: Value, token: Token) -> Diagnostic {
+        Diagnostic::new("Unexpected Type", token.line, token.row, &token.lexeme, format!("Expected type `f64`, got value of `{}`", value).as_str(), Some("Change to type `f64`."), None)
     }
 
-    fn throw_type_mismatch_error(&self, token: Token, input: String, expected: Vec<String>) -> ! {
+    fn type_mismatch_error(&self, token: Token, input: String, expected: Vec<String>) -> Diagnostic {
         let mut expected_types: String = String::new();
         
         for type_ in expected.clone() {
@@ -134,26 +258,293 @@ impl ErrorHandler {
             }
         }
         
-        self.default_error_design("Type mismatch", token.line, token.row, &token.lexeme, format!("got: `{}`, expected: {}", input, expected_types).as_str(), None, None);
+        // number, string and boolean annotations can be reached with a cast
+        let castable: bool = NumericType::from_name(&input).is_some() || ["string", "String", "boolean"].contains(&input.as_str());
+        let help: String = format!("Convert the value with `as`, like `value as {input}`.");
+
+        Diagnostic::new("Type mismatch", token.line, token.row, &token.lexeme, format!("got: `{}`, expected: {}", input, expected_types).as_str(), castable.then_some(help.as_str()), None)
     }
 
-    fn throw_immutable_var_error(&self, token: Token, name: String) -> ! {
+    fn immutable_var_error(&self, token: Token, name: String) -> Diagnostic {
         let msg: String = String::from(format!("Cannot assign to `{name}`, because `{name}` is immutable.").as_str());
         let help: String = String::from(format!("Make `{name}` mutable by adding the `mut` keyword.").as_str());
         let note: String = String::from(format!("Variables need to be mutable to be reassigned.").as_str());
 
-        self.default_error_design("Cannot assign to immutable data", token.line, token.row, &token.lexeme, msg.as_str(), Some(help.as_str()), Some(note.as_str()));
+        Diagnostic::new("Cannot assign to immutable data", token.line, token.row, &token.lexeme, msg.as_str(), Some(help.as_str()), Some(note.as_str()))
     }
 
-    fn throw_undefined_var_error(&self, token: Token) -> ! {
+    fn undefined_var_error(&self, token: Token) -> Diagnostic {
         let msg: String = String::from(format!("Variable `{}` is undefined in this scope.", token.lexeme).as_str());
         let help: String = String::from(format!("Maybe `{}` was moved to another scope or never declared?", token.lexeme).as_str());
 
-        self.default_error_design("Undefined Variable", token.line, token.row, &token.lexeme, msg.as_str(), Some(help.as_str()), None);
+        Diagnostic::new("Undefined Variable", token.line, token.row, &token.lexeme, msg.as_str(), Some(help.as_str()), None)
+    }
+
+    fn uninitialized_var_error(&self, token: Token) -> Diagnostic {
+        let msg: String = format!("`{}` is read before a value was assigned to it.", token.lexeme);
+        let help: String = format!("Assign a value to `{}` first, or give it one where it is declared.", token.lexeme);
+
+        Diagnostic::new("Uninitialized variable", token.line, token.row, &token.lexeme, msg.as_str(), Some(help.as_str()), None)
+    }
+
+    fn not_callable_error(&self, token: Token) -> Diagnostic {
+        Diagnostic::new("Not callable", token.line, token.row, &token.lexeme, "Only functions can be called", Some("Remove the `(...)` after this expression."), None)
+    }
+
+    fn arity_mismatch_error(&self, token: Token, expected: usize, got: usize) -> Diagnostic {
+        let msg: String = format!("Expected {expected} arguments but got {got}.");
+
+        Diagnostic::new("Wrong number of arguments", token.line, token.row, &token.lexeme, msg.as_str(), None, None)
+    }
+
+    fn already_declared_error(&self, token: Token) -> Diagnostic {
+        let msg: String = format!("`{}` is already declared in this scope.", token.lexeme);
+        let help: String = format!("Rename `{}` or assign to the existing variable instead.", token.lexeme);
+
+        Diagnostic::new("Variable already declared", token.line, token.row, &token.lexeme, msg.as_str(), Some(help.as_str()), None)
+    }
+
+    fn own_initializer_error(&self, token: Token) -> Diagnostic {
+        let msg: String = format!("`{}` is read in its own initializer.", token.lexeme);
+        let note: String = String::from("A local variable is not defined until its initializer has been evaluated.");
+
+        Diagnostic::new("Variable used in its own initializer", token.line, token.row, &token.lexeme, msg.as_str(), None, Some(note.as_str()))
+    }
+
+    fn top_level_return_error(&self, token: Token) -> Diagnostic {
+        Diagnostic::new("Return outside of function", token.line, token.row, &token.lexeme, "Cannot return from top-level code", Some("Remove this `return` statement."), None)
+    }
+
+    fn undefined_property_error(&self, token: Token) -> Diagnostic {
+        let msg: String = format!("No field or method named `{}` on this entity.", token.lexeme);
+
+        Diagnostic::new("Undefined Property", token.line, token.row, &token.lexeme, msg.as_str(), Some("Fields have to be declared in the entity body."), None)
+    }
+
+    fn not_an_instance_error(&self, token: Token) -> Diagnostic {
+        Diagnostic::new("Not an instance", token.line, token.row, &token.lexeme, "Only entity instances have properties", None, None)
+    }
+
+    fn not_an_entity_error(&self, token: Token) -> Diagnostic {
+        Diagnostic::new("Not an entity", token.line, token.row, &token.lexeme, "Only entities can be instantiated with `new`", None, None)
+    }
+
+    fn this_outside_entity_error(&self, token: Token) -> Diagnostic {
+        Diagnostic::new("`this` outside of entity", token.line, token.row, &token.lexeme, "Cannot use `this` outside of an entity", None, Some("`this` refers to the instance a method was called on."))
+    }
+
+    fn initializer_return_error(&self, token: Token) -> Diagnostic {
+        Diagnostic::new("Return value from initializer", token.line, token.row, &token.lexeme, "`init` cannot return a value", Some("Use `return;` to leave `init` early."), None)
+    }
+
+    fn inherit_from_self_error(&self, token: Token) -> Diagnostic {
+        Diagnostic::new("Entity inherits from itself", token.line, token.row, &token.lexeme, "An entity cannot be its own parent", None, None)
+    }
+
+    fn entity_not_open_error(&self, token: Token, name: String) -> Diagnostic {
+        let msg: String = format!("`{name}` is not open for inheritance.");
+        let help: String = format!("Declare it as `open entity {name}`.");
+        let note: String = String::from("Entities are closed by default.");
+
+        Diagnostic::new("Cannot inherit from closed entity", token.line, token.row, &token.lexeme, msg.as_str(), Some(help.as_str()), Some(note.as_str()))
+    }
+
+    fn missing_override_error(&self, token: Token) -> Diagnostic {
+        let msg: String = format!("`{}` redefines a method of a parent entity.", token.lexeme);
+        let help: String = format!("Add the `override` keyword: `override ... {}(...)`.", token.lexeme);
+
+        Diagnostic::new("Missing `override`", token.line, token.row, &token.lexeme, msg.as_str(), Some(help.as_str()), None)
+    }
+
+    fn nothing_to_override_error(&self, token: Token) -> Diagnostic {
+        let msg: String = format!("No parent entity declares a method named `{}`.", token.lexeme);
+
+        Diagnostic::new("Nothing to override", token.line, token.row, &token.lexeme, msg.as_str(), Some("Remove the `override` keyword."), None)
+    }
+
+    fn parent_outside_subentity_error(&self, token: Token) -> Diagnostic {
+        Diagnostic::new("`parent` outside of subentity", token.line, token.row, &token.lexeme, "Cannot use `parent` in an entity without a parent", Some("Inherit from an open entity with `entity Name : Parent`."), None)
+    }
+
+    fn not_a_trait_error(&self, token: Token) -> Diagnostic {
+        let msg: String = format!("`{}` is not a trait.", token.lexeme);
+
+        Diagnostic::new("Not a trait", token.line, token.row, &token.lexeme, msg.as_str(), Some("Only traits can follow `with`, use `:` to inherit from an entity."), None)
+    }
+
+    fn missing_trait_method_error(&self, token: Token, entity: String, trait_: String) -> Diagnostic {
+        let msg: String = format!("`{entity}` does not implement `{}`, which is required by `{trait_}`.", token.lexeme);
+        let help: String = format!("Add a method `{}(...)` to `{entity}`.", token.lexeme);
+        let note: String = String::from("Trait methods without a body have to be provided by every implementing entity.");
+
+        Diagnostic::new("Missing trait method", token.line, token.row, &token.lexeme, msg.as_str(), Some(help.as_str()), Some(note.as_str()))
+    }
+
+    fn trait_signature_mismatch_error(&self, token: Token, method: String, expected: usize, got: usize) -> Diagnostic {
+        let msg: String = format!("`{method}` takes {got} parameters, but the trait requires {expected}.");
+
+        Diagnostic::new("Trait method signature mismatch", token.line, token.row, &token.lexeme, msg.as_str(), None, None)
+    }
+
+    fn inaccessible_member_error(&self, token: Token, owner: String, visibility: Visibility) -> Diagnostic {
+        let (keyword, reach) = match visibility {
+            Visibility::Priv => ("priv", format!("inside `{owner}`")),
+            _ => ("prot", format!("inside `{owner}` and the entities inheriting from it")),
+        };
+        let msg: String = format!("`{}` is declared `{keyword}` in `{owner}`.", token.lexeme);
+        let help: String = format!("Mark `{}` as `pub`, or access it only {reach}.", token.lexeme);
+
+        Diagnostic::new("Inaccessible member", token.line, token.row, &token.lexeme, msg.as_str(), Some(help.as_str()), None)
+    }
+
+    fn not_an_enum_error(&self, token: Token) -> Diagnostic {
+        let msg: String = format!("`{}` is not an enum.", token.lexeme);
+
+        Diagnostic::new("Not an enum", token.line, token.row, &token.lexeme, msg.as_str(), Some("Patterns have the form `Enum.Variant(bindings)` or `_`."), None)
+    }
+
+    fn unknown_variant_error(&self, token: Token, enum_: String) -> Diagnostic {
+        let msg: String = format!("`{enum_}` has no variant named `{}`.", token.lexeme);
+
+        Diagnostic::new("Unknown variant", token.line, token.row, &token.lexeme, msg.as_str(), None, None)
+    }
+
+    fn non_exhaustive_match_error(&self, token: Token, missing: Vec<String>) -> Diagnostic {
+        let missing: Vec<String> = missing.iter().map(|variant| format!("`{variant}`")).collect();
+        let msg: String = format!("Variants {} are not covered.", missing.join(", "));
+
+        Diagnostic::new("Non-exhaustive match", token.line, token.row, &token.lexeme, msg.as_str(), Some("Add an arm for each missing variant or a `_ -> ...` arm."), None)
+    }
+
+    fn no_matching_arm_error(&self, token: Token, value: Value) -> Diagnostic {
+        let msg: String = format!("No arm matches `{value}`.");
+
+        Diagnostic::new("No matching arm", token.line, token.row, &token.lexeme, msg.as_str(), Some("Add a `_ -> ...` arm."), None)
+    }
+
+    fn uncaught_error(&self, token: Token, value: Value) -> Diagnostic {
+        let msg: String = value.to_string();
+
+        Diagnostic::new("Uncaught error", token.line, token.row, &token.lexeme, msg.as_str(), Some("Wrap the code in `try { ... } catch (e) { ... }` to handle it."), None)
+    }
+
+    fn return_in_defer_error(&self, token: Token) -> Diagnostic {
+        Diagnostic::new("Return inside defer", token.line, token.row, &token.lexeme, "Cannot return from a deferred statement", Some("Deferred statements run while the scope is already being left."), None)
+    }
+
+    fn range_bound_error(&self, token: Token, value: Value) -> Diagnostic {
+        let msg: String = format!("Expected an integer, got `{value}`.");
+
+        Diagnostic::new("Invalid range bound", token.line, token.row, &token.lexeme, msg.as_str(), Some("Ranges only span integers."), None)
+    }
+
+    fn zero_step_error(&self, token: Token) -> Diagnostic {
+        Diagnostic::new("Range step of zero", token.line, token.row, &token.lexeme, "A range cannot advance by a step of 0", Some("Use a positive step to count up or a negative one to count down."), None)
+    }
+
+    fn not_iterable_error(&self, token: Token, value: Value) -> Diagnostic {
+        let msg: String = format!("Cannot iterate over `{value}`.");
+
+        Diagnostic::new("Not iterable", token.line, token.row, &token.lexeme, msg.as_str(), Some("`for` loops accept ranges, strings, arrays and maps."), None)
+    }
+
+    fn not_indexable_error(&self, token: Token, value: Value) -> Diagnostic {
+        let msg: String = format!("Cannot index into `{value}`.");
+
+        Diagnostic::new("Not indexable", token.line, token.row, &token.lexeme, msg.as_str(), Some("Only arrays and maps can be indexed."), None)
+    }
+
+    fn invalid_index_error(&self, token: Token, value: Value) -> Diagnostic {
+        let msg: String = format!("Expected an integer index, got `{value}`.");
+
+        Diagnostic::new("Invalid index", token.line, token.row, &token.lexeme, msg.as_str(), Some("Read slices with a range like `xs[1..3]`, they cannot be assigned to."), None)
+    }
+
+    fn invalid_map_key_error(&self, token: Token, value: Value) -> Diagnostic {
+        // floats print without their `.0`, the type keeps `1.0` from looking like an integer key
+        let msg: String = format!("`{value}` ({}) cannot be used as a map key.", value.type_name());
+
+        Diagnostic::new("Invalid map key", token.line, token.row, &token.lexeme, msg.as_str(), Some("Map keys have to be strings, integers or booleans."), None)
+    }
+
+    fn missing_key_error(&self, token: Token, value: Value) -> Diagnostic {
+        let msg: String = format!("The map has no key `{value}`.");
+
+        Diagnostic::new("Missing key", token.line, token.row, &token.lexeme, msg.as_str(), Some("Check with `key in map` before reading it."), None)
+    }
+
+    fn not_a_container_error(&self, token: Token, value: Value) -> Diagnostic {
+        let msg: String = format!("Cannot look for values in `{value}`.");
+
+        Diagnostic::new("Not a container", token.line, token.row, &token.lexeme, msg.as_str(), Some("`in` looks for keys in maps, elements in arrays and substrings in strings."), None)
+    }
+
+    fn invalid_operands_error(&self, token: Token, lhs: Value, rhs: Value) -> Diagnostic {
+        let msg: String = format!("Cannot apply `{}` to `{lhs}` and `{rhs}`.", token.lexeme);
+        let help: Option<&str> = match (lhs, rhs) {
+            (Value::String(_), _) | (_, Value::String(_)) => Some("Use interpolation like \"{a}{b}\" to join a string with another value."),
+            _ => None,
+        };
+
+        Diagnostic::new("Unsupported operands", token.line, token.row, &token.lexeme, msg.as_str(), help, None)
+    }
+
+    fn integer_overflow_error(&self, token: Token, expression: String, type_: &str) -> Diagnostic {
+        let msg: String = format!("`{expression}` does not fit in `{type_}`.");
+
+        Diagnostic::new("Integer overflow", token.line, token.row, &token.lexeme, msg.as_str(), Some("Use a wider type like `i64` if the value may get this large."), None)
+    }
+
+    fn no_size_error(&self, token: Token, value: Value) -> Diagnostic {
+        let msg: String = format!("`{value}` of type `{}` has no size.", value.type_name());
+
+        Diagnostic::new("Value has no size", token.line, token.row, &token.lexeme, msg.as_str(), Some("`sizeof` takes strings, arrays, maps, tuples and numbers."), None)
+    }
+
+    fn invalid_cast_error(&self, token: Token, value: Value, type_: String) -> Diagnostic {
+        let msg: String = format!("Cannot convert `{value}` to `{type_}`.");
+
+        Diagnostic::new("Invalid cast", token.line, token.row, &token.lexeme, msg.as_str(), None, None)
+    }
+
+    fn malformed_number_error(&self, token: Token, msg: String) -> Diagnostic {
+        Diagnostic::new("Malformed number literal", token.line, token.row, &token.lexeme, msg.as_str(), None, None)
+    }
+
+    fn out_of_range_error(&self, token: Token, value: String, type_: &str) -> Diagnostic {
+        let msg: String = format!("`{value}` does not fit in `{type_}`.");
+
+        Diagnostic::new("Value out of range", token.line, token.row, &token.lexeme, msg.as_str(), None, None)
+    }
+
+    fn division_by_zero_error(&self, token: Token) -> Diagnostic {
+        Diagnostic::new("Division by zero", token.line, token.row, &token.lexeme, "The right-hand side is zero.", None, None)
+    }
+
+    fn negative_repeat_error(&self, token: Token, count: i128) -> Diagnostic {
+        let msg: String = format!("Cannot repeat a string {count} times.");
+
+        Diagnostic::new("Negative repetition", token.line, token.row, &token.lexeme, msg.as_str(), None, None)
+    }
+
+    fn invalid_escape_error(&self, token: Token, msg: &str) -> Diagnostic {
+        Diagnostic::new("Invalid escape sequence", token.line, token.row, &token.lexeme, msg, Some("Write a literal backslash as `\\\\` or use a raw string `r\"...\"`."), None)
+    }
+
+    fn destructure_mismatch_error(&self, token: Token, expected: String, value: Value) -> Diagnostic {
+        let msg: String = format!("Expected {expected}, got `{value}`.");
+
+        Diagnostic::new("Pattern does not match", token.line, token.row, &token.lexeme, msg.as_str(), Some("Add a `..rest` pattern to take any remaining elements."), None)
+    }
+
+    fn index_out_of_bounds_error(&self, token: Token, index: i128, len: usize) -> Diagnostic {
+        let msg: String = format!("Index `{index}` is out of bounds for length {len}.");
+
+        Diagnostic::new("Index out of bounds", token.line, token.row, &token.lexeme, msg.as_str(), Some("Negative indices count from the end, `-1` is the last element."), None)
     }
 
-    fn throw_fatal_error(&self) -> ! {
-        self.default_error_design("Fatal Error", 0, 0, "", "", Some("Try recompiling the program"), Some("Contact support under will.help@gmail.com."));
+    fn fatal_error(&self) -> Diagnostic {
+        Diagnostic::new("Fatal Error", 0, 0, "", "", Some("Try recompiling the program"), Some("Contact support under will.help@gmail.com."))
     }
 }
 
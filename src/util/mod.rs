@@ -1,4 +1,6 @@
-use std::{any::Any, fmt::{Debug, Display}, panic::{AssertUnwindSafe, UnwindSafe}};
+use std::{any::Any, cell::RefCell, fmt::{Debug, Display}, panic::{AssertUnwindSafe, UnwindSafe}, rc::Rc};
+
+use crate::interpreter::{callable::Callable, entity::{EntityClass, Instance, RogueTrait}, enums::{RogueEnum, VariantValue}, exception::RogueError, map::RogueMap, numeric::{self, NumericType}, range::RogueRange};
 
 pub mod error;
 pub mod error_formatter;
@@ -6,10 +8,31 @@ pub mod print_formatter;
 
 #[derive(Clone, Debug)]
 pub enum Value {
+    // `i32` and `f64`, the types of unsuffixed literals
     Integer(i32),
     Float(f64),
+    I8(i8),
+    I16(i16),
+    I64(i64),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    F32(f32),
     String(String),
     Boolean(bool),
+    Callable(Rc<dyn Callable>),
+    Entity(Rc<EntityClass>),
+    Instance(Rc<RefCell<Instance>>),
+    Trait(Rc<RogueTrait>),
+    Enum(Rc<RogueEnum>),
+    Variant(Rc<VariantValue>),
+    Error(Rc<RogueError>),
+    Range(Rc<RogueRange>),
+    // shared, so every copy of an array sees assignments to its elements
+    Array(Rc<RefCell<Vec<Value>>>),
+    Map(Rc<RefCell<RogueMap>>),
+    Tuple(Rc<Vec<Value>>),
     Null,
 }
 
@@ -32,6 +55,61 @@ impl Value {
         if let Value::String(..) = self { return true };
         return false;
     }
+
+    /// The name `typeof` reports, the same names type annotations use.
+    pub fn type_name(&self) -> String {
+        if let Some(numeric) = NumericType::of(self) {
+            return numeric.name().to_string();
+        }
+
+        match self {
+            Value::String(_) => "string".to_string(),
+            Value::Boolean(_) => "boolean".to_string(),
+            Value::Callable(_) => "function".to_string(),
+            Value::Entity(_) => "entity".to_string(),
+            Value::Instance(instance) => instance.borrow().entity.name.lexeme.clone(),
+            Value::Trait(_) => "trait".to_string(),
+            Value::Enum(_) => "enum".to_string(),
+            Value::Variant(variant) => variant.enum_.name.lexeme.clone(),
+            Value::Error(_) => "error".to_string(),
+            Value::Range(_) => "range".to_string(),
+            Value::Array(_) => "array".to_string(),
+            Value::Map(_) => "map".to_string(),
+            Value::Tuple(_) => "tuple".to_string(),
+            _ => "null".to_string(),
+        }
+    }
+
+    /// Equality behind `==` and `!=`. Numbers compare by value across every width,
+    /// strings, booleans, ranges, tuples and enum variants by their contents, and arrays, maps,
+    /// instances, functions, entities, traits, enums and errors by identity.
+    /// Values of different kinds are never equal, so `null == false` and `"1" == 1` are false.
+    pub fn equals(&self, other: &Value) -> bool {
+        if let (Some(a), Some(b)) = (NumericType::of(self), NumericType::of(other)) {
+            if a.is_float() || b.is_float() {
+                return numeric::float(self) == numeric::float(other);
+            }
+            return numeric::integer(self) == numeric::integer(other);
+        }
+
+        match (self, other) {
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::Boolean(a), Value::Boolean(b)) => a == b,
+            (Value::Null, Value::Null) => true,
+            (Value::Range(a), Value::Range(b)) => a == b,
+            (Value::Tuple(a), Value::Tuple(b)) => a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| a.equals(b)),
+            (Value::Variant(a), Value::Variant(b)) => a.same_variant(b) && a.values.iter().zip(b.values.iter()).all(|(a, b)| a.equals(b)),
+            (Value::Array(a), Value::Array(b)) => Rc::ptr_eq(a, b),
+            (Value::Map(a), Value::Map(b)) => Rc::ptr_eq(a, b),
+            (Value::Instance(a), Value::Instance(b)) => Rc::ptr_eq(a, b),
+            (Value::Callable(a), Value::Callable(b)) => Rc::ptr_eq(a, b),
+            (Value::Entity(a), Value::Entity(b)) => Rc::ptr_eq(a, b),
+            (Value::Trait(a), Value::Trait(b)) => Rc::ptr_eq(a, b),
+            (Value::Enum(a), Value::Enum(b)) => Rc::ptr_eq(a, b),
+            (Value::Error(a), Value::Error(b)) => Rc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
 }
 
 impl core::fmt::Display for Value {
@@ -39,8 +117,36 @@ impl core::fmt::Display for Value {
         match self {
             Self::Float(fl) => write!(f, "{fl}"),
             Self::Integer(i) => write!(f, "{i}"),
+            Self::I8(i) => write!(f, "{i}"),
+            Self::I16(i) => write!(f, "{i}"),
+            Self::I64(i) => write!(f, "{i}"),
+            Self::U8(i) => write!(f, "{i}"),
+            Self::U16(i) => write!(f, "{i}"),
+            Self::U32(i) => write!(f, "{i}"),
+            Self::U64(i) => write!(f, "{i}"),
+            Self::F32(fl) => write!(f, "{fl}"),
             Self::String(s) => write!(f, "{s}"),
             Self::Boolean(b) => write!(f, "{b}"),
+            Self::Callable(c) => write!(f, "<fn {}>", c.name()),
+            Self::Entity(e) => write!(f, "<entity {}>", e.name.lexeme),
+            Self::Instance(i) => write!(f, "<{} instance>", i.borrow().entity.name.lexeme),
+            Self::Trait(t) => write!(f, "<trait {}>", t.name.lexeme),
+            Self::Enum(e) => write!(f, "<enum {}>", e.name.lexeme),
+            Self::Variant(v) => write!(f, "{v}"),
+            Self::Error(e) => write!(f, "{e}"),
+            Self::Range(r) => write!(f, "{r}"),
+            Self::Array(a) => {
+                let elements: Vec<String> = a.borrow().iter().map(|element| element.to_string()).collect();
+                write!(f, "[{}]", elements.join(", "))
+            },
+            Self::Map(m) => write!(f, "{}", m.borrow()),
+            Self::Tuple(t) => {
+                let elements: Vec<String> = t.iter().map(|element| element.to_string()).collect();
+                match elements.len() {
+                    1 => write!(f, "({},)", elements[0]),
+                    _ => write!(f, "({})", elements.join(", ")),
+                }
+            },
             Self::Null => write!(f, "null"),
         }
     }